@@ -1,4 +1,6 @@
 use alloc::{
+    collections::BTreeMap,
+    format,
     string::{String, ToString},
     vec::Vec,
 };
@@ -16,7 +18,8 @@ use client_sdk::contract_indexer::{
 };
 use client_sdk::transaction_builder::TxExecutorHandler;
 use sdk::{
-    tracing, utils::as_hyle_output, Blob, Calldata, ConsensusProposalHash, RegisterContractEffect,
+    tracing, utils::as_hyle_output, verifiers::Secp256k1Blob, Blob, Calldata,
+    ConsensusProposalHash, RegisterContractEffect,
 };
 
 use client_sdk::contract_indexer::axum;
@@ -28,17 +31,24 @@ use crate::{smt::HyliGotchiWorldSMT, *};
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Debug, Clone)]
 pub struct HyliGotchiWorld {
     // NOT VERIFIED ONCHAIN
+    /// Height of the last handled transaction.
     pub last_block_height: u64,
     pub last_block_hash: ConsensusProposalHash,
 
     #[serde_as(as = "[_; 33]")]
     pub backend_pubkey: BackendPubKey,
+    pub rules: GameRules,
+    pub last_backend_nonce: u128,
     pub gotchis: HyliGotchiWorldSMT,
+    /// Session keys seen authorizing each identity in a settled transaction.
+    #[serde(skip)]
+    pub session_keys: BTreeMap<String, Vec<SessionKey>>,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct HyliGotchiWorldConstructor {
     pub backend_pubkey: BackendPubKey,
+    pub rules: GameRules,
 }
 
 impl TxExecutorHandler for HyliGotchiWorld {
@@ -58,45 +68,65 @@ impl TxExecutorHandler for HyliGotchiWorld {
         let action: HyliGotchiAction = HyliGotchiAction::from_blob_data(&blob.data)?;
         let zk_view = match action {
             HyliGotchiAction::Tick { .. } => {
-                // TODO
-                let mut clone = self.clone();
-                let next_state = clone.tick(&self.last_block_hash, self.last_block_height);
-                let tick_data = match next_state {
-                    Ok(_) => Some((
-                        clone.get_state_commitment(),
-                        self.last_block_hash.clone(),
-                        self.last_block_height,
+                let gotchis = self.sorted_gotchis();
+                let proof = match gotchis.is_empty() {
+                    true => None,
+                    false => Some(BorshableMerkleProof(
+                        self.gotchis
+                            .0
+                            .merkle_proof(gotchis.iter().map(|(key, _)| *key).collect())
+                            .expect("Failed to generate proof"),
                     )),
-                    Err(_) => None,
                 };
                 HyliGotchiWorldZkView {
-                    commitment: self.get_state_commitment(),
-                    backend_pubkey: self.backend_pubkey,
-                    tick_data,
-                    partial_data: vec![],
+                    tick_data: vec![TickHyliGotchiWorldData {
+                        proof,
+                        gotchis: gotchis
+                            .into_iter()
+                            .map(|(key, gotchi)| (<[u8; 32]>::from(key), gotchi))
+                            .collect(),
+                    }],
+                    ..self.zk_view()
                 }
             }
-            HyliGotchiAction::Init(ident, ..)
-            | HyliGotchiAction::CleanPoop(ident, ..)
-            | HyliGotchiAction::FeedFood(ident, ..)
-            | HyliGotchiAction::FeedSweets(ident, ..)
-            | HyliGotchiAction::FeedVitamins(ident, ..)
-            | HyliGotchiAction::Resurrect(ident, ..) => {
-                // We unwrap-or-default because if we didn't find it, we still want to prove failure.
-                let gotchi = self.get(&ident).unwrap_or_default();
+            HyliGotchiAction::RotateBackendKey(..)
+            | HyliGotchiAction::ResetWorld(..)
+            | HyliGotchiAction::Migrate(..) => self.zk_view(),
+            HyliGotchiAction::Init(..)
+            | HyliGotchiAction::CleanPoop(..)
+            | HyliGotchiAction::FeedFood(..)
+            | HyliGotchiAction::FeedSweets(..)
+            | HyliGotchiAction::FeedVitamins(..)
+            | HyliGotchiAction::Heal(..)
+            | HyliGotchiAction::Resurrect(..)
+            | HyliGotchiAction::Play(..)
+            | HyliGotchiAction::Sleep(..)
+            | HyliGotchiAction::Wake(..)
+            | HyliGotchiAction::Transfer(..)
+            | HyliGotchiAction::Adopt(..)
+            | HyliGotchiAction::AllowBreeding(..)
+            | HyliGotchiAction::Breed(..) => {
+                let ids = action.gotchi_ids();
+                // The contract rejects repeated gotchis before checking the proof, which
+                // must still be built over distinct keys.
+                let mut keys = ids.iter().map(HyliGotchi::compute_key).collect::<Vec<_>>();
+                keys.sort();
+                keys.dedup();
                 HyliGotchiWorldZkView {
-                    commitment: self.get_state_commitment(),
-                    backend_pubkey: self.backend_pubkey,
-                    tick_data: None,
                     partial_data: vec![PartialHyliGotchiWorldData {
                         proof: BorshableMerkleProof(
                             self.gotchis
                                 .0
-                                .merkle_proof(vec![HyliGotchi::compute_key(&ident)])
+                                .merkle_proof(keys)
                                 .expect("Failed to generate proof"),
                         ),
-                        gotchi,
+                        // We unwrap-or-default because if we didn't find it, we still want to prove failure.
+                        gotchis: ids
+                            .iter()
+                            .map(|id| self.get(id).unwrap_or_default())
+                            .collect(),
                     }],
+                    ..self.zk_view()
                 }
             }
         };
@@ -115,18 +145,28 @@ impl TxExecutorHandler for HyliGotchiWorld {
 
         next_view.partial_data.extend(initial_view.partial_data);
         next_view.commitment = initial_view.commitment;
-        next_view.tick_data = next_view.tick_data.or(initial_view.tick_data);
+        next_view.root = initial_view.root;
+        next_view.gotchi_count = initial_view.gotchi_count;
+        next_view.backend_pubkey = initial_view.backend_pubkey;
+        next_view.rules = initial_view.rules;
+        next_view.last_backend_nonce = initial_view.last_backend_nonce;
+        next_view.tick_data.extend(initial_view.tick_data);
 
         borsh::to_vec(&next_view).map_err(|e| format!("Failed to serialize combined view: {e}"))
     }
 
     fn get_state_commitment(&self) -> StateCommitment {
-        get_state_commitment(*self.gotchis.0.root(), self.backend_pubkey)
+        get_state_commitment(
+            *self.gotchis.0.root(),
+            self.gotchi_count(),
+            self.backend_pubkey,
+            &self.rules,
+            self.last_backend_nonce,
+        )
     }
 
     fn handle(&mut self, calldata: &Calldata) -> anyhow::Result<sdk::HyleOutput> {
-        let initial_state_commitment =
-            get_state_commitment(*self.gotchis.0.root(), self.backend_pubkey);
+        let initial_state_commitment = self.get_state_commitment();
 
         let (action, ctx) = sdk::utils::parse_raw_calldata::<HyliGotchiAction>(calldata)
             .map_err(|e| anyhow!("Failed to parse calldata: {}", e))?;
@@ -135,67 +175,99 @@ impl TxExecutorHandler for HyliGotchiWorld {
             return Err(anyhow!("Transaction context is missing"));
         };
 
-        if let HyliGotchiAction::Tick(nonce) = &action {
-            let tick_ok = check_tick_commitment(calldata, *nonce, &self.backend_pubkey)
-                .and(self.tick(&self.last_block_hash.clone(), self.last_block_height));
-            if tick_ok.is_ok() {
-                self.last_block_hash = tx_ctx.block_hash.clone();
-                self.last_block_height = tx_ctx.block_height.0;
-            }
-
-            if calldata.tx_hash
-                == sdk::TxHash(
-                    "aa361866f8783cde596772e12086317a809463e3580de4d9d57e1e9a45085ced".to_string(),
+        // Track the chain head, so that reads can bring gotchis up to date.
+        self.last_block_hash = tx_ctx.block_hash.clone();
+        self.last_block_height = tx_ctx.block_height.0;
+
+        // Actions signed by the backend, which act on the world as a whole.
+        let backend_res = match &action {
+            HyliGotchiAction::Tick(nonce) => Some(
+                self.check_backend_action(calldata, &tick_signed_data(*nonce), *nonce)
+                    .and_then(|_| self.tick(&tx_ctx.block_hash, tx_ctx.block_height.0))
+                    .map(|_| {
+                        self.last_backend_nonce = *nonce;
+                        self.forget_stale_session_keys();
+                        "Tick"
+                    }),
+            ),
+            HyliGotchiAction::RotateBackendKey(nonce, new_key) => Some(
+                self.check_backend_action(
+                    calldata,
+                    &rotate_backend_key_signed_data(*nonce, new_key),
+                    *nonce,
                 )
-            {
-                tracing::warn!("Resetting contract state to initial state");
-                // This TxHash was resetting the contract state to the initial state
-                // We need to reset the actual contract state to this initial state
-                let constructor = HyliGotchiWorldConstructor {
-                    backend_pubkey: self.backend_pubkey,
-                };
-
-                let world = HyliGotchiWorld::new(&constructor);
-                *self = world;
-            }
+                .map(|_| {
+                    tracing::warn!("Rotating backend public key");
+                    self.backend_pubkey = *new_key;
+                    self.last_backend_nonce = *nonce;
+                    "RotateBackendKey"
+                }),
+            ),
+            HyliGotchiAction::ResetWorld(nonce) => Some(
+                self.check_backend_action(calldata, &reset_world_signed_data(*nonce), *nonce)
+                    .map(|_| {
+                        tracing::warn!("Resetting contract state to an empty world");
+                        self.gotchis = HyliGotchiWorldSMT::default();
+                        self.session_keys.clear();
+                        self.last_backend_nonce = *nonce;
+                        "ResetWorld"
+                    }),
+            ),
+            HyliGotchiAction::Migrate(nonce, rules) => Some(
+                self.check_backend_action(calldata, &migrate_signed_data(*nonce, rules), *nonce)
+                    .map(|_| {
+                        tracing::warn!("Migrating contract state to new game rules: {rules:?}");
+                        self.rules = rules.clone();
+                        self.last_backend_nonce = *nonce;
+                        "Migrate"
+                    }),
+            ),
+            _ => None,
+        };
 
+        if let Some(res) = backend_res {
             return Ok(as_hyle_output(
                 initial_state_commitment,
-                get_state_commitment(*self.gotchis.0.root(), self.backend_pubkey),
+                self.get_state_commitment(),
                 calldata,
-                &mut match tick_ok {
-                    Ok(_) => Ok(("Tick".as_bytes().to_vec(), ctx, alloc::vec![])),
-                    Err(e) => Err(e.to_string()),
-                },
+                &mut res.map(|name| (name.as_bytes().to_vec(), ctx, alloc::vec![])),
             ));
         }
 
-        let user = match &action {
-            HyliGotchiAction::Init(ident, ..) => ident,
-            HyliGotchiAction::CleanPoop(ident, ..) => ident,
-            HyliGotchiAction::FeedFood(ident, ..) => ident,
-            HyliGotchiAction::FeedSweets(ident, ..) => ident,
-            HyliGotchiAction::FeedVitamins(ident, ..) => ident,
-            HyliGotchiAction::Tick(..) => unreachable!(),
-            HyliGotchiAction::Resurrect(ident, ..) => ident,
-        }
-        .clone();
+        let keys = action
+            .gotchi_ids()
+            .iter()
+            .map(HyliGotchi::compute_key)
+            .collect::<Vec<_>>();
 
-        let mut gotchi = self
-            .gotchis
-            .0
-            .get(&HyliGotchi::compute_key(&user))
+        let mut gotchis = keys
+            .iter()
+            .map(|key| self.gotchis.0.get(key))
+            .collect::<Result<Vec<_>, _>>()
             .context("Gotchi not found in the state")?;
 
-        let res = handle_nontick_action(&mut gotchi, &user, action, tx_ctx, calldata);
-
-        self.gotchis
-            .0
-            .update(HyliGotchi::compute_key(&user), gotchi)
-            .context("Failed to update gotchi")?;
+        let res = handle_nontick_action(
+            &mut gotchis,
+            &calldata.identity,
+            action,
+            &ctx.contract_name,
+            &self.rules,
+            tx_ctx,
+            calldata,
+        );
+
+        // A failed action must leave the state untouched, as in the zkVM.
+        if res.is_ok() {
+            self.record_session_keys(calldata);
+            for (key, gotchi) in keys.into_iter().zip(gotchis) {
+                self.gotchis
+                    .0
+                    .update(key, gotchi)
+                    .context("Failed to update gotchi")?;
+            }
+        }
 
-        let next_state_commitment =
-            get_state_commitment(*self.gotchis.0.root(), self.backend_pubkey);
+        let next_state_commitment = self.get_state_commitment();
 
         Ok(as_hyle_output(
             initial_state_commitment,
@@ -213,6 +285,7 @@ impl ContractHandler for HyliGotchiWorld {
     async fn api(store: ContractHandlerStore<HyliGotchiWorld>) -> (Router<()>, OpenApi) {
         let (router, api) = OpenApiRouter::default()
             .routes(routes!(get_state))
+            .routes(routes!(get_gotchis))
             .routes(routes!(get_metadata))
             .split_for_parts();
 
@@ -220,27 +293,178 @@ impl ContractHandler for HyliGotchiWorld {
     }
 }
 
-const IDENTITY_HEADER: &str = "x-identity";
+pub const IDENTITY_HEADER: &str = "x-identity";
+pub const PUBLIC_KEY_HEADER: &str = "x-public-key";
+pub const SIGNATURE_HEADER: &str = "x-signature";
+pub const TIMESTAMP_HEADER: &str = "x-timestamp";
+
+/// Contract name of the native secp256k1 verifier, used by wallet session keys.
+pub const SECP256K1_CONTRACT: &str = "secp256k1";
+
+/// How long, in milliseconds, a signed identity stays valid.
+pub const AUTH_SIGNATURE_VALIDITY_MS: u64 = 5 * 60 * 1000;
+
+/// Compressed secp256k1 public key of a wallet session key.
+pub type SessionPubKey = [u8; 33];
+
+/// Number of blocks after its last settled transaction during which a session key can still
+/// read the gotchis of its identity. Removing a key from the wallet, or letting it expire,
+/// leaves no trace in this contract, so a key that stops signing is forgotten after this delay.
+pub const SESSION_KEY_VALIDITY_BLOCKS: u64 = 86_400;
+
+/// Session key seen authorizing an identity in a settled transaction.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SessionKey {
+    pub public_key: SessionPubKey,
+    /// Height of the last settled transaction signed by the key.
+    pub last_used_at: u64,
+}
+
+impl SessionKey {
+    pub fn is_valid_at(&self, block_height: u64) -> bool {
+        block_height
+            <= self
+                .last_used_at
+                .saturating_add(SESSION_KEY_VALIDITY_BLOCKS)
+    }
+}
+
+/// Message a player signs with its session key to prove its identity at `timestamp`.
+pub fn auth_signed_data(identity: &str, timestamp: u64) -> Vec<u8> {
+    format!("HyliGotchiAuth:{identity}:{timestamp}").into_bytes()
+}
+
+/// Checks an ECDSA signature over the sha256 `digest` of a message.
+pub fn verify_secp256k1_signature(
+    digest: [u8; 32],
+    public_key: &[u8],
+    signature: &[u8],
+) -> Result<()> {
+    let public_key =
+        secp256k1::PublicKey::from_slice(public_key).context("Invalid session public key")?;
+    let signature =
+        secp256k1::ecdsa::Signature::from_compact(signature).context("Invalid signature")?;
+    secp256k1::Secp256k1::verification_only()
+        .verify_ecdsa(
+            secp256k1::Message::from_digest(digest),
+            &signature,
+            &public_key,
+        )
+        .context("Signature does not match")
+}
 
+/// Checks the signed identity sent in the auth headers, and returns the session key that
+/// signed it. The key still has to be bound to the identity by the caller.
+pub fn check_auth_signature(
+    identity: &str,
+    timestamp: u64,
+    public_key: &str,
+    signature: &str,
+) -> Result<SessionPubKey> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .context("System clock is before the epoch")?
+        .as_millis() as u64;
+    if now.abs_diff(timestamp) > AUTH_SIGNATURE_VALIDITY_MS {
+        return Err(anyhow!("Signed identity has expired"));
+    }
+
+    let public_key: SessionPubKey = hex::decode(public_key)
+        .ok()
+        .and_then(|key| key.try_into().ok())
+        .ok_or_else(|| anyhow!("Public key must be 33 hex-encoded bytes"))?;
+    let signature = hex::decode(signature).context("Signature must be hex-encoded")?;
+
+    let digest = Sha256::digest(auth_signed_data(identity, timestamp)).into();
+    verify_secp256k1_signature(digest, &public_key, &signature)?;
+    Ok(public_key)
+}
+
+/// Decodes the secp256k1 blob of a session key, if `blob` is one.
+pub fn decode_secp256k1_blob(blob: &Blob) -> Option<Secp256k1Blob> {
+    if blob.contract_name.0 != SECP256K1_CONTRACT {
+        return None;
+    }
+    borsh::from_slice(&blob.data.0).ok()
+}
+
+/// Identity proven by the signed auth headers of a request, with the session key that signed it.
 #[derive(Debug)]
-struct AuthHeaders {
-    identity: String,
+pub struct AuthHeaders {
+    pub identity: String,
+    pub public_key: SessionPubKey,
 }
 
 impl AuthHeaders {
-    fn from_headers(headers: &HeaderMap) -> Result<Self, AppError> {
-        let identity = headers
-            .get(IDENTITY_HEADER)
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| {
-                AppError(
-                    StatusCode::UNAUTHORIZED,
-                    anyhow::anyhow!("Missing identity"),
-                )
-            })?
-            .to_string();
+    pub fn from_headers(headers: &HeaderMap) -> Result<Self, AppError> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .ok_or_else(|| {
+                    AppError(
+                        StatusCode::UNAUTHORIZED,
+                        anyhow::anyhow!("Missing {name} header"),
+                    )
+                })
+        };
+
+        let identity = header(IDENTITY_HEADER)?.to_string();
+        let timestamp = header(TIMESTAMP_HEADER)?.parse().map_err(|_| {
+            AppError(
+                StatusCode::UNAUTHORIZED,
+                anyhow::anyhow!("Invalid {TIMESTAMP_HEADER} header"),
+            )
+        })?;
+        let public_key = check_auth_signature(
+            &identity,
+            timestamp,
+            header(PUBLIC_KEY_HEADER)?,
+            header(SIGNATURE_HEADER)?,
+        )
+        .map_err(|e| AppError(StatusCode::UNAUTHORIZED, e))?;
+
+        Ok(AuthHeaders {
+            identity,
+            public_key,
+        })
+    }
 
-        Ok(AuthHeaders { identity })
+    /// Only keys that recently authorized the identity on chain can read its gotchis.
+    /// An identity that never acted on chain has no gotchi to read.
+    pub fn check_session_key(&self, world: &HyliGotchiWorld) -> Result<(), AppError> {
+        let Some(keys) = world.session_keys.get(&self.identity) else {
+            return Err(AppError(
+                StatusCode::NOT_FOUND,
+                anyhow!("No gotchi found for identity {}", self.identity),
+            ));
+        };
+        match keys.iter().any(|key| {
+            key.public_key == self.public_key && key.is_valid_at(world.last_block_height)
+        }) {
+            true => Ok(()),
+            false => Err(AppError(
+                StatusCode::UNAUTHORIZED,
+                anyhow!("Unknown session key for identity {}", self.identity),
+            )),
+        }
+    }
+
+    /// The wallet blobs must be signed by the same session key that signed the headers.
+    /// The wallet contract then checks on chain that this key belongs to the identity.
+    pub fn check_session_signature(&self, secp_blob: &Secp256k1Blob) -> Result<(), AppError> {
+        if secp_blob.public_key != self.public_key {
+            return Err(AppError(
+                StatusCode::UNAUTHORIZED,
+                anyhow!(
+                    "Wallet blobs are not signed by the session key of {}",
+                    self.identity
+                ),
+            ));
+        }
+
+        verify_secp256k1_signature(secp_blob.data, &secp_blob.public_key, &secp_blob.signature)
+            .map_err(|e| AppError(StatusCode::UNAUTHORIZED, e))
     }
 }
 
@@ -259,18 +483,22 @@ pub async fn get_state(
     let auth_headers = AuthHeaders::from_headers(&headers)?;
     let store = state.read().await;
 
+    if let Some(s) = &store.state {
+        auth_headers.check_session_key(s)?;
+    }
+
     store
         .state
         .clone()
         .and_then(|s| {
-            match s
-                .gotchis
-                .0
-                .get(&HyliGotchi::compute_key(&Identity(auth_headers.identity)))
-            {
-                Ok(gotchi) => match gotchi.name.is_empty() {
+            let key = HyliGotchi::compute_key(&GotchiId::new(Identity(auth_headers.identity), 0));
+            match s.gotchis.0.get(&key) {
+                Ok(mut gotchi) => match gotchi.name.is_empty() {
                     true => None, // No gotchi found for this identity
-                    false => Some(gotchi),
+                    false => {
+                        gotchi.catch_up(&key, &s.rules, s.last_block_height);
+                        Some(gotchi)
+                    }
                 },
                 Err(_) => None, // Error retrieving gotchi
             }
@@ -282,6 +510,43 @@ pub async fn get_state(
         ))
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OwnedGotchi {
+    pub slot: u32,
+    #[serde(flatten)]
+    pub gotchi: HyliGotchi,
+}
+
+#[utoipa::path(
+    get,
+    path = "/gotchis",
+    tag = "Contract",
+    responses(
+        (status = OK, description = "Get json state of all the gotchis of the caller")
+    )
+)]
+pub async fn get_gotchis(
+    State(state): State<ContractHandlerStore<HyliGotchiWorld>>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    let auth_headers = AuthHeaders::from_headers(&headers)?;
+    let store = state.read().await;
+
+    if let Some(s) = &store.state {
+        auth_headers.check_session_key(s)?;
+    }
+
+    store
+        .state
+        .as_ref()
+        .map(|s| s.owned_gotchis(&Identity(auth_headers.identity)))
+        .map(Json)
+        .ok_or(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow!("No state found for contract '{}'", store.contract_name),
+        ))
+}
+
 #[serde_with::serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Metadata {
@@ -289,6 +554,11 @@ pub struct Metadata {
     pub last_block_hash: ConsensusProposalHash,
     #[serde_as(as = "[_; 33]")]
     pub backend_pubkey: BackendPubKey,
+    pub rules: GameRules,
+    pub last_backend_nonce: u128,
+    /// Root and size of the gotchis SMT, to build proofs of actions touching no leaf.
+    pub root: [u8; 32],
+    pub gotchi_count: u64,
 }
 
 #[utoipa::path(
@@ -311,6 +581,10 @@ pub async fn get_metadata(
             last_block_height: s.last_block_height,
             last_block_hash: s.last_block_hash,
             backend_pubkey: s.backend_pubkey,
+            root: (*s.gotchis.0.root()).into(),
+            gotchi_count: s.gotchi_count(),
+            rules: s.rules,
+            last_backend_nonce: s.last_backend_nonce,
         })
         .map(Json)
         .ok_or(AppError(
@@ -325,11 +599,147 @@ impl HyliGotchiWorld {
             last_block_height: 0,
             last_block_hash: ConsensusProposalHash::default(),
             backend_pubkey: args.backend_pubkey,
+            rules: args.rules.clone(),
+            last_backend_nonce: 0,
             gotchis: HyliGotchiWorldSMT::default(),
+            session_keys: BTreeMap::new(),
         }
     }
-    pub fn get(&self, user: &Identity) -> Option<HyliGotchi> {
-        self.gotchis.0.get(&HyliGotchi::compute_key(user)).ok()
+
+    /// Remembers the session keys that signed for the caller of a settled transaction.
+    /// The wallet contract only lets such a transaction settle with keys of that identity.
+    fn record_session_keys(&mut self, calldata: &Calldata) {
+        for (_, blob) in calldata.blobs.iter() {
+            let Some(secp_blob) = decode_secp256k1_blob(blob) else {
+                continue;
+            };
+            if secp_blob.identity != calldata.identity {
+                continue;
+            }
+            let keys = self
+                .session_keys
+                .entry(calldata.identity.0.clone())
+                .or_default();
+            match keys
+                .iter_mut()
+                .find(|key| key.public_key == secp_blob.public_key)
+            {
+                Some(key) => key.last_used_at = self.last_block_height,
+                None => keys.push(SessionKey {
+                    public_key: secp_blob.public_key,
+                    last_used_at: self.last_block_height,
+                }),
+            }
+        }
+    }
+
+    /// Forgets the session keys that signed nothing for `SESSION_KEY_VALIDITY_BLOCKS`.
+    fn forget_stale_session_keys(&mut self) {
+        let block_height = self.last_block_height;
+        self.session_keys.retain(|_, keys| {
+            keys.retain(|key| key.is_valid_at(block_height));
+            !keys.is_empty()
+        });
+    }
+    /// Checks the backend signature and nonce of a backend-signed action.
+    fn check_backend_action(
+        &self,
+        calldata: &Calldata,
+        data_to_sign: &[u8],
+        nonce: u128,
+    ) -> Result<(), String> {
+        check_backend_signature(calldata, data_to_sign, &self.backend_pubkey)?;
+        check_backend_nonce(nonce, self.last_backend_nonce)
+    }
+
+    /// View of the committed state, without any leaf.
+    fn zk_view(&self) -> HyliGotchiWorldZkView {
+        HyliGotchiWorldZkView {
+            commitment: self.get_state_commitment(),
+            root: (*self.gotchis.0.root()).into(),
+            gotchi_count: self.gotchi_count(),
+            tick_data: vec![],
+            backend_pubkey: self.backend_pubkey,
+            rules: self.rules.clone(),
+            last_backend_nonce: self.last_backend_nonce,
+            partial_data: vec![],
+        }
+    }
+
+    pub fn get(&self, id: &GotchiId) -> Option<HyliGotchi> {
+        self.gotchis.0.get(&HyliGotchi::compute_key(id)).ok()
+    }
+
+    /// Living or dead gotchis of an identity, brought up to date with the chain head.
+    pub fn owned_gotchis(&self, owner: &Identity) -> Vec<OwnedGotchi> {
+        (0..self.rules.max_gotchis_per_identity)
+            .filter_map(|slot| {
+                let key = HyliGotchi::compute_key(&GotchiId::new(owner.clone(), slot));
+                let mut gotchi = self.gotchis.0.get(&key).ok()?;
+                if gotchi.name.is_empty() {
+                    return None;
+                }
+                gotchi.catch_up(&key, &self.rules, self.last_block_height);
+                Some(OwnedGotchi { slot, gotchi })
+            })
+            .collect()
+    }
+
+    /// Runs a player action on copies of the gotchis it touches, as the contract would at the
+    /// block of `calldata`, and returns them in the order of `HyliGotchiAction::gotchi_ids`.
+    pub fn simulate(
+        &self,
+        action: HyliGotchiAction,
+        contract_name: &ContractName,
+        calldata: &Calldata,
+    ) -> Result<Vec<HyliGotchi>, String> {
+        let Some(tx_ctx) = &calldata.tx_ctx else {
+            return Err("Transaction context is missing".to_string());
+        };
+
+        let mut gotchis = action
+            .gotchi_ids()
+            .iter()
+            .map(|id| self.get(id).unwrap_or_default())
+            .collect::<Vec<_>>();
+
+        handle_nontick_action(
+            &mut gotchis,
+            &calldata.identity,
+            action,
+            contract_name,
+            &self.rules,
+            tx_ctx,
+            calldata,
+        )?;
+
+        Ok(gotchis)
+    }
+
+    /// Number of gotchis in the world, as committed on chain.
+    pub fn gotchi_count(&self) -> u64 {
+        self.gotchis
+            .0
+            .store()
+            .leaves_map()
+            .values()
+            .filter(|gotchi| !gotchi.name.is_empty())
+            .count() as u64
+    }
+
+    /// All gotchis of the world, sorted by key.
+    pub fn sorted_gotchis(&self) -> Vec<(H256, HyliGotchi)> {
+        let mut gotchis = self
+            .gotchis
+            .0
+            .store()
+            .leaves_map()
+            .iter()
+            .filter(|(_, gotchi)| !gotchi.name.is_empty())
+            .map(|(key, gotchi)| (*key, gotchi.clone()))
+            .collect::<Vec<_>>();
+        gotchis.sort_by_key(|(key, _)| *key); // Need deterministic ordering.
+        gotchis
     }
 }
 
@@ -352,7 +762,7 @@ impl Display for HyliGotchiWorld {
         let size = self.gotchis.0.store().leaves_map().iter().count();
         write!(
             f,
-            "Last tick: {}, Total gotchis: {}",
+            "Last block: {}, Total gotchis: {}",
             self.last_block_height, size
         )
     }
@@ -15,9 +15,7 @@ use rand_seeder::{SipHasher, SipRng};
 use sdk::merkle_utils::{BorshableMerkleProof, SHA256Hasher};
 use sdk::secp256k1::CheckSecp256k1;
 use sdk::tracing::info;
-use sdk::{
-    BlobIndex, BlockHash, ConsensusProposalHash, ContractName, Identity, RunResult, StateCommitment,
-};
+use sdk::{BlobIndex, BlockHash, ContractName, Identity, RunResult, StateCommitment};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use sparse_merkle_tree::traits::Value;
@@ -37,21 +35,47 @@ pub const DEFAULT_BACKEND_PUBLIC_KEY: BackendPubKey = [
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct HyliGotchiWorldZkView {
     pub commitment: sdk::StateCommitment,
-    pub tick_data: Option<(sdk::StateCommitment, ConsensusProposalHash, u64)>,
+    /// Root of the gotchis SMT matching `commitment`, for actions that touch no leaf.
+    pub root: [u8; 32],
+    /// Number of non-empty leaves under `root`, so that a tick cannot leave any gotchi out.
+    pub gotchi_count: u64,
+    pub tick_data: Vec<TickHyliGotchiWorldData>,
     pub backend_pubkey: BackendPubKey,
+    pub rules: GameRules,
+    pub last_backend_nonce: u128,
     pub partial_data: Vec<PartialHyliGotchiWorldData>,
 }
 
+/// Leaves touched by a player action, in the order of `HyliGotchiAction::gotchi_ids`,
+/// with a multi-leaf proof against the current root.
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct PartialHyliGotchiWorldData {
     pub proof: BorshableMerkleProof,
-    pub gotchi: HyliGotchi,
+    pub gotchis: Vec<HyliGotchi>,
+}
+
+/// Leaves processed by a tick, with a multi-leaf proof against the current root.
+/// They must be every gotchi of the world, sorted by key.
+/// The proof is `None` only when the world has no gotchi yet.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct TickHyliGotchiWorldData {
+    pub proof: Option<BorshableMerkleProof>,
+    pub gotchis: Vec<([u8; 32], HyliGotchi)>,
 }
 
-fn get_state_commitment(root: H256, pubkey: BackendPubKey) -> StateCommitment {
+fn get_state_commitment(
+    root: H256,
+    gotchi_count: u64,
+    pubkey: BackendPubKey,
+    rules: &GameRules,
+    last_backend_nonce: u128,
+) -> StateCommitment {
     let mut hasher = Sha256::new();
     hasher.update(root.as_slice());
+    hasher.update(gotchi_count.to_le_bytes());
     hasher.update(pubkey);
+    hasher.update(borsh::to_vec(rules).expect("Failed to encode GameRules"));
+    hasher.update(last_backend_nonce.to_le_bytes());
     let result = hasher.finalize();
     StateCommitment(result.to_vec())
 }
@@ -68,30 +92,224 @@ impl sdk::ZkContract for HyliGotchiWorldZkView {
 
         // Special case tick
         if let HyliGotchiAction::Tick(nonce) = action {
-            // This is a trusted action, just update the commitment.
-            check_tick_commitment(calldata, nonce, &self.backend_pubkey)?;
-            let Some(tick_data) = &self.tick_data else {
-                return Err("Tick data must be set for tick action".to_string());
+            // If we don't have state for this calldata, then the proof cannot be generated and we must panic.
+            // Popped before any check, so a rejected tick does not shift the data of the next ones.
+            let TickHyliGotchiWorldData { proof, gotchis } = self
+                .tick_data
+                .pop()
+                .expect("No tick data available for the contract state");
+
+            check_backend_signature(calldata, &tick_signed_data(nonce), &self.backend_pubkey)?;
+            check_backend_nonce(nonce, self.last_backend_nonce)?;
+
+            let new_root = match proof {
+                None => {
+                    // Nothing to tick in an empty world.
+                    if self.gotchi_count != 0
+                        || self.commitment != self.state_commitment(H256::zero())
+                    {
+                        panic!("Tick data is missing the gotchis of the contract state");
+                    }
+                    H256::zero()
+                }
+                Some(proof) => {
+                    // Neighbours are only known from every gotchi, each one once.
+                    if gotchis.len() as u64 != self.gotchi_count
+                        || gotchis.iter().any(|(_, gotchi)| gotchi.name.is_empty())
+                        || gotchis.windows(2).any(|pair| pair[0].0 >= pair[1].0)
+                    {
+                        panic!("Tick data does not match the gotchis of the contract state");
+                    }
+
+                    let mut gotchis = gotchis
+                        .into_iter()
+                        .map(|(key, gotchi)| (H256::from(key), gotchi))
+                        .collect::<Vec<_>>();
+
+                    let leaves = gotchis
+                        .iter()
+                        .map(|(key, gotchi)| (*key, gotchi.to_h256()))
+                        .collect::<Vec<_>>();
+                    self.check_proof(&proof, leaves)?;
+
+                    tick_gotchis(
+                        &mut gotchis,
+                        &self.rules,
+                        &tx_ctx.block_hash,
+                        tx_ctx.block_height.0,
+                    );
+
+                    let leaves = gotchis
+                        .iter()
+                        .map(|(key, gotchi)| (*key, gotchi.to_h256()))
+                        .collect::<Vec<_>>();
+                    proof
+                        .0
+                        .compute_root::<SHA256Hasher>(leaves)
+                        .expect("Failed to compute new root")
+                }
             };
-            self.commitment = tick_data.0.clone();
+
+            // Now update the commitment
+            self.last_backend_nonce = nonce;
+            self.set_root(new_root);
+
             return Ok(("Tick".as_bytes().to_vec(), ctx, alloc::vec![]));
         }
 
-        // Not an identity contract.
-        if calldata.identity.0.ends_with(ctx.contract_name.0.as_str()) {
-            return Err("This contract does not support identity actions".to_string());
+        // Admin actions, signed by the backend and touching no leaf
+        match action {
+            HyliGotchiAction::RotateBackendKey(nonce, new_key) => {
+                check_backend_signature(
+                    calldata,
+                    &rotate_backend_key_signed_data(nonce, &new_key),
+                    &self.backend_pubkey,
+                )?;
+                check_backend_nonce(nonce, self.last_backend_nonce)?;
+
+                let root = self.checked_root();
+                self.backend_pubkey = new_key;
+                self.last_backend_nonce = nonce;
+                self.set_root(root);
+
+                return Ok(("RotateBackendKey".as_bytes().to_vec(), ctx, alloc::vec![]));
+            }
+            HyliGotchiAction::ResetWorld(nonce) => {
+                check_backend_signature(
+                    calldata,
+                    &reset_world_signed_data(nonce),
+                    &self.backend_pubkey,
+                )?;
+                check_backend_nonce(nonce, self.last_backend_nonce)?;
+
+                self.checked_root();
+                self.last_backend_nonce = nonce;
+                self.gotchi_count = 0;
+                self.set_root(H256::zero());
+
+                return Ok(("ResetWorld".as_bytes().to_vec(), ctx, alloc::vec![]));
+            }
+            HyliGotchiAction::Migrate(nonce, rules) => {
+                check_backend_signature(
+                    calldata,
+                    &migrate_signed_data(nonce, &rules),
+                    &self.backend_pubkey,
+                )?;
+                check_backend_nonce(nonce, self.last_backend_nonce)?;
+
+                let root = self.checked_root();
+                self.rules = rules;
+                self.last_backend_nonce = nonce;
+                self.set_root(root);
+
+                return Ok(("Migrate".as_bytes().to_vec(), ctx, alloc::vec![]));
+            }
+            _ => {}
         }
 
         // If we don't have state for this calldata, then the proof cannot be generated and we must panic.
-        let PartialHyliGotchiWorldData { proof, mut gotchi } = self
+        let PartialHyliGotchiWorldData { proof, mut gotchis } = self
             .partial_data
             .pop()
             .expect("No partial data available for the contract state");
 
+        // Not an identity contract.
+        if calldata.identity.0.ends_with(ctx.contract_name.0.as_str()) {
+            return Err("This contract does not support identity actions".to_string());
+        }
+
         let user = &calldata.identity;
-        let account_key = HyliGotchi::compute_key(user);
-        let leaves = vec![(account_key, gotchi.to_h256())];
+        let keys = action
+            .gotchi_ids()
+            .iter()
+            .map(HyliGotchi::compute_key)
+            .collect::<Vec<_>>();
+        if keys.is_empty() {
+            return Err("Backend actions are not supported in this context".to_string());
+        }
+        // A leaf given twice would be written twice, the last write winning.
+        action.check_distinct_gotchis()?;
+        if keys.len() != gotchis.len() {
+            panic!("Partial data does not match the gotchis of the action");
+        }
+        let leaves = keys
+            .iter()
+            .zip(gotchis.iter())
+            .map(|(key, gotchi)| (*key, gotchi.to_h256()))
+            .collect();
+        self.check_proof(&proof, leaves)?;
+        let touched_count = count_gotchis(&gotchis);
+
+        // Execute the given action
+        let res = handle_nontick_action(
+            &mut gotchis,
+            user,
+            action,
+            &ctx.contract_name,
+            &self.rules,
+            tx_ctx,
+            calldata,
+        )?;
+
+        // Now update the commitment
+        let leaves = keys
+            .iter()
+            .zip(gotchis.iter())
+            .map(|(key, gotchi)| (*key, gotchi.to_h256()))
+            .collect();
+        let new_root = proof
+            .0
+            .compute_root::<SHA256Hasher>(leaves)
+            .expect("Failed to compute new root");
+
+        self.gotchi_count = self.gotchi_count - touched_count + count_gotchis(&gotchis);
+        self.set_root(new_root);
+
+        Ok((res.as_bytes().to_vec(), ctx, alloc::vec![]))
+    }
 
+    /// In this example, we serialize the full state on-chain.
+    fn commit(&self) -> sdk::StateCommitment {
+        self.commitment.clone()
+    }
+}
+
+impl HyliGotchiWorldZkView {
+    fn state_commitment(&self, root: H256) -> StateCommitment {
+        get_state_commitment(
+            root,
+            self.gotchi_count,
+            self.backend_pubkey,
+            &self.rules,
+            self.last_backend_nonce,
+        )
+    }
+
+    /// Returns the tracked SMT root, after checking it against the committed state.
+    fn checked_root(&self) -> H256 {
+        let root = H256::from(self.root);
+        if self.commitment != self.state_commitment(root) {
+            panic!(
+                "State commitment mismatch: expected {:?}, got {:?}",
+                self.commitment,
+                self.state_commitment(root)
+            );
+        }
+        root
+    }
+
+    /// Moves the view to a new SMT root, updating the commitment accordingly.
+    fn set_root(&mut self, root: H256) {
+        self.root = root.into();
+        self.commitment = self.state_commitment(root);
+    }
+
+    /// Checks that the given leaves belong to the committed state.
+    fn check_proof(
+        &self,
+        proof: &BorshableMerkleProof,
+        leaves: Vec<(H256, H256)>,
+    ) -> Result<(), String> {
         // Validate internal consistency, then check hash.
         let root = proof
             .0
@@ -101,13 +319,13 @@ impl sdk::ZkContract for HyliGotchiWorldZkView {
         let verified = proof
             .0
             .clone()
-            .verify::<SHA256Hasher>(&root, leaves.clone())
+            .verify::<SHA256Hasher>(&root, leaves)
             .map_err(|e| format!("Failed to verify proof: {e}"))?;
-        if self.commitment != get_state_commitment(root, self.backend_pubkey) {
+        if self.commitment != self.state_commitment(root) {
             panic!(
                 "State commitment mismatch: expected {:?}, got {:?}",
                 self.commitment,
-                get_state_commitment(root, self.backend_pubkey)
+                self.state_commitment(root)
             );
         }
 
@@ -116,216 +334,682 @@ impl sdk::ZkContract for HyliGotchiWorldZkView {
             panic!("Proof verification failed for the contract state");
         }
 
-        // Execute the given action
-        let res = handle_nontick_action(&mut gotchi, user, action, tx_ctx, calldata)?;
-
-        // Now update the commitment
-        let leaves = vec![(account_key, gotchi.to_h256())];
-        let new_root = proof
-            .0
-            .compute_root::<SHA256Hasher>(leaves)
-            .expect("Failed to compute new root");
-
-        self.commitment = get_state_commitment(new_root, self.backend_pubkey);
-
-        Ok((res.as_bytes().to_vec(), ctx, alloc::vec![]))
-    }
-
-    /// In this example, we serialize the full state on-chain.
-    fn commit(&self) -> sdk::StateCommitment {
-        self.commitment.clone()
+        Ok(())
     }
 }
 
 impl sdk::TransactionalZkContract for HyliGotchiWorldZkView {
-    type State = sdk::StateCommitment;
+    type State = (
+        sdk::StateCommitment,
+        [u8; 32],
+        u64,
+        BackendPubKey,
+        GameRules,
+        u128,
+    );
 
     fn initial_state(&self) -> Self::State {
-        self.commitment.clone()
+        (
+            self.commitment.clone(),
+            self.root,
+            self.gotchi_count,
+            self.backend_pubkey,
+            self.rules.clone(),
+            self.last_backend_nonce,
+        )
     }
 
     fn revert(&mut self, initial_state: Self::State) {
-        self.commitment = initial_state;
+        (
+            self.commitment,
+            self.root,
+            self.gotchi_count,
+            self.backend_pubkey,
+            self.rules,
+            self.last_backend_nonce,
+        ) = initial_state;
     }
 }
 
+/// Number of leaves holding a gotchi, empty leaves being absent from the tree.
+fn count_gotchis(gotchis: &[HyliGotchi]) -> u64 {
+    gotchis
+        .iter()
+        .filter(|gotchi| !gotchi.name.is_empty())
+        .count() as u64
+}
+
+/// Runs a player action on the gotchis it touches, given in the order of `gotchi_ids`.
 pub fn handle_nontick_action(
-    gotchi: &mut HyliGotchi,
+    gotchis: &mut [HyliGotchi],
     user: &Identity,
     action: HyliGotchiAction,
+    contract_name: &ContractName,
+    rules: &GameRules,
     tx_ctx: &sdk::TxContext,
     calldata: &sdk::Calldata,
 ) -> Result<String, String> {
-    match action {
-        HyliGotchiAction::Init(ident, name) => {
-            if ident != *user {
-                return Err("You can only initialize your own gotchi".to_string());
-            }
-            if !gotchi.name.is_empty() {
-                return Err(format!("Gotchi already exists for user {}", ident.0));
-            }
-            gotchi.new_gotchi(name, &tx_ctx.block_hash, tx_ctx.block_height.0)
+    let Some(id) = action.gotchi_id() else {
+        return Err("Backend actions are not supported in this context".to_string());
+    };
+    action.check_distinct_gotchis()?;
+    if id.owner != *user {
+        return Err("You can only act on your own gotchis".to_string());
+    }
+
+    // Bring the gotchis up to date before acting on them.
+    for (id, gotchi) in action.gotchi_ids().iter().zip(gotchis.iter_mut()) {
+        gotchi.catch_up(&HyliGotchi::compute_key(id), rules, tx_ctx.block_height.0);
+    }
+
+    let Some((gotchi, others)) = gotchis.split_first_mut() else {
+        return Err("No gotchi given for this action".to_string());
+    };
+
+    let res = match action {
+        HyliGotchiAction::Init(id, name) => {
+            HyliGotchi::check_slot(&id, rules)?;
+            HyliGotchi::check_name(&name)?;
+            gotchi.check_vacant(&id)?;
+            let key = HyliGotchi::compute_key(&id);
+            let res =
+                gotchi.new_gotchi(name, &key, rules, &tx_ctx.block_hash, tx_ctx.block_height.0);
+            gotchi.uid = birth_uid(&calldata.tx_hash, &key);
+            res
         }
-        HyliGotchiAction::CleanPoop(ident, _nonce) => {
-            if ident != *user {
-                return Err("You can only initialize your own gotchi".to_string());
-            }
-            if gotchi.name.is_empty() {
-                return Err(format!("Gotchi does not exist for user {}", ident.0));
-            }
-            gotchi.clean_poop(&tx_ctx.block_hash)
+        HyliGotchiAction::CleanPoop(id, _nonce) => {
+            gotchi.check_exists(&id)?;
+            gotchi.clean_poop(rules, &tx_ctx.block_hash)
         }
-        HyliGotchiAction::FeedFood(ident, food_amount) => {
-            if ident != *user {
-                return Err("You can only initialize your own gotchi".to_string());
-            }
-            if gotchi.name.is_empty() {
-                return Err(format!("Gotchi does not exist for user {}", ident.0));
+        HyliGotchiAction::Play(id, _nonce) => {
+            gotchi.check_exists(&id)?;
+            gotchi.play(rules)
+        }
+        HyliGotchiAction::Sleep(id, _nonce) => {
+            gotchi.check_exists(&id)?;
+            gotchi.sleep(rules, tx_ctx.block_height.0)
+        }
+        HyliGotchiAction::Wake(id, _nonce) => {
+            gotchi.check_exists(&id)?;
+            gotchi.wake()
+        }
+        HyliGotchiAction::FeedFood(id, food_amount) => {
+            gotchi.check_exists(&id)?;
+            let points = check_nutrient_transfer(
+                calldata,
+                user,
+                contract_name,
+                rules,
+                Nutrient::Food,
+                food_amount,
+            )?;
+
+            gotchi.feed_food(points, rules, tx_ctx.block_height.0, &tx_ctx.block_hash)
+        }
+        HyliGotchiAction::FeedSweets(id, sweets_amount) => {
+            gotchi.check_exists(&id)?;
+            let points = check_nutrient_transfer(
+                calldata,
+                user,
+                contract_name,
+                rules,
+                Nutrient::Sweets,
+                sweets_amount,
+            )?;
+
+            gotchi.feed_sweets(points, rules, tx_ctx.block_height.0, &tx_ctx.block_hash)
+        }
+        HyliGotchiAction::FeedVitamins(id, vitamins_amount) => {
+            gotchi.check_exists(&id)?;
+            let points = check_nutrient_transfer(
+                calldata,
+                user,
+                contract_name,
+                rules,
+                Nutrient::Vitamins,
+                vitamins_amount,
+            )?;
+
+            gotchi.feed_vitamins(points, rules, tx_ctx.block_height.0, &tx_ctx.block_hash)
+        }
+        HyliGotchiAction::Heal(id, medicine_amount) => {
+            gotchi.check_exists(&id)?;
+            let points = check_nutrient_transfer(
+                calldata,
+                user,
+                contract_name,
+                rules,
+                Nutrient::Medicine,
+                medicine_amount,
+            )?;
+
+            gotchi.heal(points, rules)
+        }
+        HyliGotchiAction::Tick(..)
+        | HyliGotchiAction::RotateBackendKey(..)
+        | HyliGotchiAction::ResetWorld(..)
+        | HyliGotchiAction::Migrate(..) => {
+            Err("Backend actions are not supported in this context".to_string())
+        }
+        HyliGotchiAction::Transfer(id, to) => {
+            gotchi.check_exists(&id)?;
+            // Transferring a gotchi to its owner withdraws the offer.
+            gotchi.pending_owner = (to != id.owner).then_some(to);
+            Ok(match &gotchi.pending_owner {
+                Some(to) => format!("Gotchi {} is offered to {}", gotchi.name, to.0),
+                None => format!("Gotchi {} is no longer offered", gotchi.name),
+            })
+        }
+        HyliGotchiAction::Adopt(from, to) => {
+            let [adopted] = others else {
+                return Err("Adoption needs the adopted gotchi and its new slot".to_string());
+            };
+            gotchi.check_exists(&from)?;
+            if gotchi.pending_owner.as_ref() != Some(user) {
+                return Err(format!("Gotchi {from} is not offered to {}", user.0));
             }
-            // Find the oranj transfer blob
-            let oranj_transfer_blob_index = calldata
-                .blobs
-                .iter()
-                .position(|(_, b)| b.contract_name == ContractName("oranj".to_string()))
-                .ok_or_else(|| "Missing Oranj transfer blob".to_string())?;
-
-            let transfer_action = sdk::utils::parse_structured_blob::<SmtTokenAction>(
-                &calldata.blobs,
-                &sdk::BlobIndex(oranj_transfer_blob_index),
-            )
-            .ok_or_else(|| "Failed to decode Oranj transfer action".to_string())?
-            .data
-            .parameters;
-
-            let SmtTokenAction::Transfer {
-                sender,
-                recipient,
-                amount,
-            } = transfer_action
-            else {
-                return Err("Failed to decode Oranj transfer action".to_string());
+            HyliGotchi::check_slot(&to, rules)?;
+            adopted.check_vacant(&to)?;
+            *adopted = core::mem::take(gotchi);
+            adopted.pending_owner = None;
+            // The new owner decides who the gotchi breeds with.
+            adopted.breeding_partner = None;
+            Ok(format!("Gotchi {} adopted by {}", adopted.name, user.0))
+        }
+        HyliGotchiAction::AllowBreeding(id, partner_id) => {
+            gotchi.check_exists(&id)?;
+            // Allowing a gotchi to breed with itself withdraws the permission, and only
+            // touches its own leaf.
+            gotchi.breeding_partner = match others {
+                [] => None,
+                [partner] => {
+                    partner.check_exists(&partner_id)?;
+                    Some((partner_id, partner.uid))
+                }
+                _ => return Err("Breeding permission needs the gotchi and its partner".to_string()),
             };
-            if sender != *user {
-                return Err("You can only feed your own gotchi".to_string());
+            Ok(match &gotchi.breeding_partner {
+                Some((partner, _)) => format!("Gotchi {} can breed with {partner}", gotchi.name),
+                None => format!("Gotchi {} can no longer breed", gotchi.name),
+            })
+        }
+        HyliGotchiAction::Breed(parent_id, partner_id, child_id, name) => {
+            let [partner, child] = others else {
+                return Err("Breeding needs both parents and the offspring slot".to_string());
+            };
+            if parent_id.owner != *user {
+                return Err("You can only breed your own gotchis".to_string());
             }
-            if recipient != "hyligotchi2".into() {
-                return Err("You have to send oranj to the hyligotchi2 contract".to_string());
+            for (id, parent) in [(&parent_id, &*gotchi), (&partner_id, &*partner)] {
+                parent.check_exists(id)?;
+                if parent.health != HyliGotchiHealth::Healthy {
+                    return Err(format!("Gotchi {id} is not healthy enough to breed"));
+                }
+                if !parent.is_adult() {
+                    return Err(format!("Gotchi {id} must be an adult to breed"));
+                }
             }
-            if amount != food_amount as u128 {
+            if partner_id.owner != *user
+                && partner.breeding_partner != Some((parent_id.clone(), gotchi.uid))
+            {
                 return Err(format!(
-                    "Invalid amount in Oranj blob. Expected {food_amount}, got {amount}"
+                    "Gotchi {partner_id} is not allowed to breed with {parent_id}"
                 ));
             }
-
-            gotchi.feed_food(food_amount, tx_ctx.block_height.0, &tx_ctx.block_hash)
+            HyliGotchi::check_slot(&child_id, rules)?;
+            HyliGotchi::check_name(&name)?;
+            child.check_vacant(&child_id)?;
+
+            let mut hash = SipHasher::new();
+            hash.write(tx_ctx.block_hash.0.as_bytes());
+            hash.write(HyliGotchi::compute_key(&parent_id).as_slice());
+            hash.write(HyliGotchi::compute_key(&partner_id).as_slice());
+            let mut rng = SipRng::seed_from_u64(hash.finish());
+
+            *child = HyliGotchi::new(name, rules, tx_ctx.block_height.0);
+            child.uid = birth_uid(&calldata.tx_hash, &HyliGotchi::compute_key(&child_id));
+            child.genome = Genome::inherit(&mut rng, rules, &gotchi.genome, &partner.genome);
+            partner.breeding_partner = None;
+
+            Ok(format!(
+                "Gotchi {} was born from {} and {}",
+                child.name, gotchi.name, partner.name
+            ))
         }
-        HyliGotchiAction::FeedSweets(ident, sweets_amount) => {
-            if ident != *user {
-                return Err("You can only initialize your own gotchi".to_string());
+        HyliGotchiAction::Resurrect(id, amount) => {
+            gotchi.check_exists(&id)?;
+            let cost = gotchi.resurrect_cost(rules);
+            if amount != cost {
+                return Err(format!(
+                    "Resurrecting gotchi {id} costs {cost} {}, got {amount}",
+                    rules.resurrect_token
+                ));
             }
-            if gotchi.name.is_empty() {
-                return Err(format!("Gotchi does not exist for user {}", ident.0));
+            if cost != 0 {
+                let transfer_blob_index = calldata
+                    .blobs
+                    .iter()
+                    .position(|(_, b)| b.contract_name == rules.resurrect_token)
+                    .ok_or_else(|| format!("Missing {} transfer blob", rules.resurrect_token))?;
+                check_token_transfer(
+                    calldata,
+                    user,
+                    contract_name,
+                    transfer_blob_index,
+                    &rules.resurrect_token,
+                    cost,
+                )?;
             }
 
-            // Find the oxygen transfer blob
-            let oxygen_transfer_blob_index = calldata
-                .blobs
+            gotchi.resurrect_gotchi(rules, tx_ctx.block_height.0)
+        }
+    };
+
+    if res.is_ok() {
+        for gotchi in gotchis.iter_mut() {
+            gotchi.reseed(&tx_ctx.block_hash);
+        }
+    }
+    res
+}
+
+/// Identifier of a gotchi born in the transaction `tx_hash` in the leaf `key`, which no other
+/// gotchi can be given.
+fn birth_uid(tx_hash: &sdk::TxHash, key: &H256) -> u64 {
+    let mut hash = SipHasher::new();
+    hash.write(tx_hash.0.as_bytes());
+    hash.write(key.as_slice());
+    hash.finish()
+}
+
+/// Finds the registered token transfer paying for `nutrient`, checks that it moves `amount`
+/// tokens from `user` to this contract, and returns the nutrient points it is worth.
+fn check_nutrient_transfer(
+    calldata: &sdk::Calldata,
+    user: &Identity,
+    contract_name: &ContractName,
+    rules: &GameRules,
+    nutrient: Nutrient,
+    amount: u64,
+) -> Result<u64, String> {
+    // Find the transfer blob of a token registered for this nutrient
+    let (transfer_blob_index, token) = calldata
+        .blobs
+        .iter()
+        .enumerate()
+        .find_map(|(index, (_, b))| {
+            rules
+                .tokens
                 .iter()
-                .position(|(_, b)| b.contract_name == ContractName("oxygen".to_string()))
-                .ok_or_else(|| "Missing oxygen transfer blob".to_string())?;
+                .find(|t| t.nutrient == nutrient && t.contract_name == b.contract_name)
+                .map(|token| (index, token))
+        })
+        .ok_or_else(|| format!("Missing {nutrient} transfer blob"))?;
+
+    check_token_transfer(
+        calldata,
+        user,
+        contract_name,
+        transfer_blob_index,
+        &token.contract_name,
+        amount,
+    )?;
+
+    Ok(amount.saturating_mul(token.points_per_unit))
+}
 
-            let transfer_action = sdk::utils::parse_structured_blob::<SmtTokenAction>(
-                &calldata.blobs,
-                &sdk::BlobIndex(oxygen_transfer_blob_index),
-            )
-            .ok_or_else(|| "Failed to decode oxygen transfer action".to_string())?
-            .data
-            .parameters;
-
-            let SmtTokenAction::Transfer {
-                sender,
-                recipient,
-                amount,
-            } = transfer_action
-            else {
-                return Err("Failed to decode oxygen transfer action".to_string());
-            };
-            if sender != *user {
-                return Err("You can only feed your own gotchi".to_string());
-            }
-            if recipient != "hyligotchi2".into() {
-                return Err("You have to send oxygen to the hyligotchi2 contract".to_string());
-            }
-            if amount != sweets_amount as u128 {
-                return Err(format!(
-                    "Invalid amount in oxygen blob. Expected {sweets_amount}, got {amount}"
-                ));
-            }
+/// Checks that the `token` blob at `transfer_blob_index` transfers `amount` tokens from `user`
+/// to this contract.
+fn check_token_transfer(
+    calldata: &sdk::Calldata,
+    user: &Identity,
+    contract_name: &ContractName,
+    transfer_blob_index: usize,
+    token: &ContractName,
+    amount: u64,
+) -> Result<(), String> {
+    let transfer_action = sdk::utils::parse_structured_blob::<SmtTokenAction>(
+        &calldata.blobs,
+        &sdk::BlobIndex(transfer_blob_index),
+    )
+    .ok_or_else(|| format!("Failed to decode {token} transfer action"))?
+    .data
+    .parameters;
+
+    let SmtTokenAction::Transfer {
+        sender,
+        recipient,
+        amount: transferred,
+    } = transfer_action
+    else {
+        return Err(format!("Failed to decode {token} transfer action"));
+    };
+    if sender != *user {
+        return Err("You can only pay with your own tokens".to_string());
+    }
+    if recipient.0 != contract_name.0 {
+        return Err(format!(
+            "You have to send {token} to the {contract_name} contract"
+        ));
+    }
+    if transferred != amount as u128 {
+        return Err(format!(
+            "Invalid amount in {token} blob. Expected {amount}, got {transferred}"
+        ));
+    }
 
-            gotchi.feed_sweets(sweets_amount, tx_ctx.block_height.0, &tx_ctx.block_hash)
+    Ok(())
+}
+
+/// Kind of nutrient a token feeds to a gotchi.
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
+pub enum Nutrient {
+    Food,
+    Sweets,
+    Vitamins,
+    /// Cures indigestion.
+    Medicine,
+}
+
+impl Display for Nutrient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Nutrient::Food => "food",
+                Nutrient::Sweets => "sweets",
+                Nutrient::Vitamins => "vitamins",
+                Nutrient::Medicine => "medicine",
+            },
+        )
+    }
+}
+
+/// A token accepted as payment to feed gotchis.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NutrientToken {
+    pub contract_name: ContractName,
+    pub nutrient: Nutrient,
+    /// Nutrient points given for each token transferred.
+    pub points_per_unit: u64,
+}
+
+pub const GENOME_LEN: usize = 4;
+
+/// Kind of creature a gotchi is, each rendered with its own assets.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Species {
+    Hyligotchi,
+    Aliengotchi,
+}
+
+impl Display for Species {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Species::Hyligotchi => "hyligotchi",
+                Species::Aliengotchi => "aliengotchi",
+            },
+        )
+    }
+}
+
+/// Sets how easily an underfed gotchi gets sick.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Temperament {
+    Playful,
+    Calm,
+    Grumpy,
+}
+
+impl Temperament {
+    /// Calm gotchis get sick half as often as playful ones, grumpy gotchis twice as often.
+    pub fn sick_odds(&self, odds: u64) -> u64 {
+        match self {
+            Temperament::Playful => odds,
+            Temperament::Calm => odds.saturating_mul(2),
+            Temperament::Grumpy => odds.div_ceil(2),
         }
-        HyliGotchiAction::FeedVitamins(ident, vitamins_amount) => {
-            if ident != *user {
-                return Err("You can only initialize your own gotchi".to_string());
-            }
-            if gotchi.name.is_empty() {
-                return Err(format!("Gotchi does not exist for user {}", ident.0));
-            }
+    }
+}
 
-            // Find the vitamin transfer blob
-            let vitamin_transfer_blob_index = calldata
-                .blobs
-                .iter()
-                .position(|(_, b)| b.contract_name == ContractName("vitamin".to_string()))
-                .ok_or_else(|| "Missing vitamin transfer blob".to_string())?;
+impl Display for Temperament {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Temperament::Playful => "playful",
+                Temperament::Calm => "calm",
+                Temperament::Grumpy => "grumpy",
+            },
+        )
+    }
+}
 
-            let transfer_action = sdk::utils::parse_structured_blob::<SmtTokenAction>(
-                &calldata.blobs,
-                &sdk::BlobIndex(vitamin_transfer_blob_index),
-            )
-            .ok_or_else(|| "Failed to decode vitamin transfer action".to_string())?
-            .data
-            .parameters;
-
-            let SmtTokenAction::Transfer {
-                sender,
-                recipient,
-                amount,
-            } = transfer_action
-            else {
-                return Err("Failed to decode vitamin transfer action".to_string());
-            };
-            if sender != *user {
-                return Err("You can only feed your own gotchi".to_string());
-            }
-            if recipient != "hyligotchi2".into() {
-                return Err("You have to send vitamins to the hyligotchi2 contract".to_string());
-            }
-            if amount != vitamins_amount as u128 {
-                return Err(format!(
-                    "Invalid amount in vitamin blob. Expected {vitamins_amount}, got {amount}"
-                ));
-            }
-            gotchi.feed_vitamins(vitamins_amount, tx_ctx.block_height.0, &tx_ctx.block_hash)
+/// Sets how fast a gotchi gets through its food and sweets.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metabolism {
+    Normal,
+    Slow,
+    Fast,
+}
+
+impl Metabolism {
+    /// Points of food or sweets lost at a decay step.
+    fn decrease(&self, rng: &mut impl Rng) -> u64 {
+        match self {
+            Metabolism::Normal => rng.random_range(0..=1),
+            Metabolism::Slow => u64::from(one_in(rng, 3)),
+            Metabolism::Fast => rng.random_range(0..=2),
+        }
+    }
+}
+
+impl Display for Metabolism {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Metabolism::Normal => "normal",
+                Metabolism::Slow => "slow",
+                Metabolism::Fast => "fast",
+            },
+        )
+    }
+}
+
+/// Genes of a gotchi, passed down to its offspring.
+/// The all-zero genome is a playful hyligotchi of normal metabolism.
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Default, PartialEq,
+)]
+pub struct Genome {
+    pub genes: [u8; GENOME_LEN],
+}
+
+impl Genome {
+    const SPECIES: usize = 0;
+    const COLOR: usize = 1;
+    const TEMPERAMENT: usize = 2;
+    const METABOLISM: usize = 3;
+
+    pub fn random(rng: &mut impl Rng) -> Genome {
+        Genome {
+            genes: rng.random(),
         }
-        HyliGotchiAction::Tick(..) => {
-            Err("Tick action is not supported in this context".to_string())
+    }
+
+    /// One gotchi in eight is an aliengotchi.
+    pub fn species(&self) -> Species {
+        match self.genes[Self::SPECIES] >= 224 {
+            true => Species::Aliengotchi,
+            false => Species::Hyligotchi,
         }
-        HyliGotchiAction::Resurrect(ident, _nonce) => {
-            if ident != *user {
-                return Err("You can only resurrect your own gotchi".to_string());
-            }
-            if gotchi.name.is_empty() {
-                return Err(format!("Gotchi does not exist for user {}", ident.0));
-            }
-            gotchi.resurrect_gotchi(tx_ctx.block_height.0)
+    }
+
+    /// Hue of the gotchi.
+    pub fn color(&self) -> u8 {
+        self.genes[Self::COLOR]
+    }
+
+    pub fn temperament(&self) -> Temperament {
+        match self.genes[Self::TEMPERAMENT] % 3 {
+            0 => Temperament::Playful,
+            1 => Temperament::Calm,
+            _ => Temperament::Grumpy,
+        }
+    }
+
+    pub fn metabolism(&self) -> Metabolism {
+        match self.genes[Self::METABOLISM] % 3 {
+            0 => Metabolism::Normal,
+            1 => Metabolism::Slow,
+            _ => Metabolism::Fast,
+        }
+    }
+
+    /// Each gene is taken from either parent, and may mutate into a random value.
+    pub fn inherit(rng: &mut impl Rng, rules: &GameRules, a: &Genome, b: &Genome) -> Genome {
+        let mut genes = [0u8; GENOME_LEN];
+        for (i, gene) in genes.iter_mut().enumerate() {
+            *gene = if one_in(rng, rules.mutation_odds) {
+                rng.random()
+            } else if rng.random_bool(0.5) {
+                a.genes[i]
+            } else {
+                b.genes[i]
+            };
+        }
+        Genome { genes }
+    }
+}
+
+/// Balance of the game, set at construction and committed in the contract state.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct GameRules {
+    pub max_food: u64,
+    pub max_sweets: u64,
+    pub max_vitamins: u64,
+    pub max_happiness: u64,
+    /// Weight of a newborn gotchi, which a gotchi fed with food only keeps.
+    pub normal_weight: u64,
+    /// From this weight on, a gotchi is obese: it plays less and gets sick more easily.
+    pub obese_weight: u64,
+    /// Happiness gained by playing with a gotchi.
+    pub play_happiness: u64,
+    /// Number of blocks an adult gotchi sleeps for once put to bed.
+    pub sleep_duration: u64,
+    /// Number of blocks between two decay steps of a gotchi.
+    pub decay_interval: u64,
+    /// Number of blocks a gotchi must have been sick for before it can die.
+    pub death_window: u64,
+    /// Number of blocks after its death before a gotchi can be resurrected.
+    pub resurrect_delay: u64,
+    /// Token paid to resurrect a gotchi.
+    pub resurrect_token: ContractName,
+    /// Tokens paid to resurrect a gotchi, for each time it died.
+    pub resurrect_cost: u64,
+    /// A gotchi that died `max_deaths` times stays dead, zero for no limit.
+    pub max_deaths: u64,
+    /// A gotchi poops with a chance of one in `poop_odds` at each decay step.
+    pub poop_odds: u64,
+    /// Number of poops that can pile up around a gotchi.
+    pub max_poops: u64,
+    /// An underfed gotchi gets sick with a chance of one in `sick_odds` at each decay step.
+    pub sick_odds: u64,
+    /// A gotchi sick for longer than `death_window` dies with a chance of one in `death_odds`.
+    pub death_odds: u64,
+    /// At each tick, a gotchi catches the contagious disease of a neighbour with a chance of
+    /// one in `contagion_odds`.
+    pub contagion_odds: u64,
+    /// Number of blocks a healthy gotchi fed up to its maximum vitamins is immune to contagion.
+    pub vaccine_duration: u64,
+    /// Number of gotchis a single identity can own.
+    pub max_gotchis_per_identity: u32,
+    /// Number of blocks after birth before an egg hatches into a baby.
+    pub hatch_age: u64,
+    /// Number of blocks after birth before a baby grows into a teen.
+    pub teen_age: u64,
+    /// Number of blocks after birth before a gotchi is an adult and can breed.
+    pub adult_age: u64,
+    /// Number of blocks after birth before an adult becomes an elder.
+    pub elder_age: u64,
+    /// Number of blocks after birth before a gotchi dies of old age, zero for no limit.
+    pub lifespan: u64,
+    /// Each gene of an offspring mutates with a chance of one in `mutation_odds`.
+    pub mutation_odds: u64,
+    /// Tokens accepted to feed gotchis.
+    pub tokens: Vec<NutrientToken>,
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        GameRules {
+            max_food: 10,
+            max_sweets: 10,
+            max_vitamins: 10,
+            max_happiness: 10,
+            normal_weight: 10,
+            obese_weight: 20,
+            play_happiness: 3,
+            sleep_duration: 7_200,
+            decay_interval: 3_600,
+            death_window: 50_000,
+            resurrect_delay: 100,
+            resurrect_token: ContractName("medicine".to_string()),
+            resurrect_cost: 1,
+            max_deaths: 0,
+            poop_odds: 11,
+            max_poops: 5,
+            sick_odds: 2,
+            death_odds: 2,
+            contagion_odds: 4,
+            vaccine_duration: 72_000,
+            max_gotchis_per_identity: 3,
+            hatch_age: 3_600,
+            teen_age: 36_000,
+            adult_age: 72_000,
+            elder_age: 1_728_000,
+            lifespan: 2_592_000,
+            mutation_odds: 20,
+            tokens: vec![
+                NutrientToken {
+                    contract_name: ContractName("oranj".to_string()),
+                    nutrient: Nutrient::Food,
+                    points_per_unit: 1,
+                },
+                NutrientToken {
+                    contract_name: ContractName("oxygen".to_string()),
+                    nutrient: Nutrient::Sweets,
+                    points_per_unit: 1,
+                },
+                NutrientToken {
+                    contract_name: ContractName("vitamin".to_string()),
+                    nutrient: Nutrient::Vitamins,
+                    points_per_unit: 1,
+                },
+                NutrientToken {
+                    contract_name: ContractName("medicine".to_string()),
+                    nutrient: Nutrient::Medicine,
+                    points_per_unit: 1,
+                },
+            ],
         }
     }
 }
 
-pub const MAX_FOOD: u64 = 10;
-pub const MAX_SWEETS: u64 = 10;
-pub const MAX_VITAMINS: u64 = 10;
+/// Returns true with a chance of one in `odds`, and never when `odds` is zero.
+fn one_in(rng: &mut impl Rng, odds: u64) -> bool {
+    odds != 0 && rng.random_range(0..odds) == 0
+}
 
 /// The state of the contract, that is totally serialized on-chain
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Default)]
@@ -334,7 +1018,10 @@ pub struct HyliGotchi {
     pub activity: HyliGotchiActivity,
     pub health: HyliGotchiHealth,
     pub death_count: u64,
-    pub pooped: bool,
+    /// Block height of the last death of the gotchi.
+    pub died_at: u64,
+    /// Poops piled up since the last cleaning, up to `max_poops`.
+    pub poops: u64,
     pub born_at: u64,
     pub food: u64,
     pub last_food_block_height: u64,
@@ -342,69 +1029,312 @@ pub struct HyliGotchi {
     pub last_sweets_at: u64,
     pub vitamins: u64,
     pub last_vitamins_at: u64,
+    pub last_decay_at: u64,
+    pub happiness: u64,
+    /// Grows with sweets, and gets back down to normal when playing.
+    pub weight: u64,
+    /// Block height until which the gotchi cannot catch a disease from its neighbours.
+    pub vaccinated_until: u64,
+    /// Block height at which a sleeping gotchi wakes up on its own.
+    pub wakes_at: u64,
+    /// Identity allowed to adopt this gotchi, as offered by its owner.
+    pub pending_owner: Option<Identity>,
+    pub genome: Genome,
+    pub stage: LifeStage,
+    /// Block height at which the gotchi entered its current life stage.
+    pub stage_since: u64,
+    /// Gotchi of another owner this one is allowed to breed with, along with its `uid` so that
+    /// the permission does not pass on to a gotchi later taking the same slot.
+    pub breeding_partner: Option<(GotchiId, u64)>,
+    /// Drawn from the hash of the last block that wrote the gotchi, and mixed into its decay
+    /// steps so that they cannot be known before that block.
+    pub decay_seed: u64,
+    /// Drawn from the transaction giving birth to the gotchi, and kept for its whole life.
+    pub uid: u64,
 }
 
 impl HyliGotchi {
-    pub fn new(name: String, block_height: u64) -> Self {
+    pub fn new(name: String, rules: &GameRules, block_height: u64) -> Self {
         HyliGotchi {
             name,
             activity: HyliGotchiActivity::Idle,
             health: HyliGotchiHealth::Healthy,
             death_count: 0,
-            pooped: false,
+            died_at: 0,
+            poops: 0,
             born_at: block_height,
-            food: MAX_FOOD,
+            food: rules.max_food,
             last_food_block_height: block_height,
-            sweets: MAX_SWEETS,
+            sweets: rules.max_sweets,
             last_sweets_at: block_height,
-            vitamins: MAX_VITAMINS,
+            vitamins: rules.max_vitamins,
             last_vitamins_at: block_height,
+            last_decay_at: block_height,
+            happiness: rules.max_happiness,
+            weight: rules.normal_weight,
+            vaccinated_until: 0,
+            wakes_at: 0,
+            pending_owner: None,
+            genome: Genome::default(),
+            stage: LifeStage::Egg,
+            stage_since: block_height,
+            breeding_partner: None,
+            decay_seed: 0,
+            uid: 0,
         }
     }
 
-    pub fn is_hungry(&self) -> bool {
-        self.food < MAX_FOOD || self.sweets < MAX_SWEETS || self.vitamins < MAX_FOOD
+    pub fn is_adult(&self) -> bool {
+        self.stage == LifeStage::Adult
+    }
+
+    /// Odds for an underfed gotchi to get sick at a decay step.
+    pub fn sick_odds(&self, rules: &GameRules) -> u64 {
+        let mut odds = self.genome.temperament().sick_odds(rules.sick_odds);
+        if self.stage == LifeStage::Elder {
+            odds = odds.div_ceil(2);
+        }
+        if self.is_happy(rules) {
+            odds = odds.saturating_mul(2);
+        }
+        if self.is_obese(rules) {
+            odds = odds.div_ceil(2);
+        }
+        // Each poop lying around makes sickness more likely
+        odds.div_ceil(1 + self.poops)
+    }
+
+    pub fn is_obese(&self, rules: &GameRules) -> bool {
+        self.weight >= rules.obese_weight
+    }
+
+    /// From 0 when the poops reached `max_poops` up to `max_poops` for a clean gotchi.
+    pub fn cleanliness(&self, rules: &GameRules) -> u64 {
+        rules.max_poops.saturating_sub(self.poops)
+    }
+
+    /// Points of `nutrient` the gotchi can still take, `None` when there is no limit.
+    /// A single point of medicine cures an indigestion.
+    pub fn appetite(&self, nutrient: Nutrient, rules: &GameRules) -> Option<u64> {
+        match nutrient {
+            Nutrient::Food => Some(rules.max_food.saturating_sub(self.food)),
+            Nutrient::Sweets => Some(rules.max_sweets.saturating_sub(self.sweets)),
+            Nutrient::Vitamins => Some(rules.max_vitamins.saturating_sub(self.vitamins)),
+            Nutrient::Medicine => Some(u64::from(self.is_sick_with(Disease::Indigestion))),
+        }
+    }
+
+    /// Rejects feeding more than the gotchi can take, so that no token is paid for nothing.
+    fn check_appetite(
+        &self,
+        nutrient: Nutrient,
+        points: u64,
+        rules: &GameRules,
+    ) -> Result<(), String> {
+        match self.appetite(nutrient, rules) {
+            Some(appetite) if points > appetite => Err(format!(
+                "Gotchi {} can only take {appetite} more {nutrient}, got {points}",
+                self.name
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Rejects slots beyond what an identity can own.
+    fn check_slot(id: &GotchiId, rules: &GameRules) -> Result<(), String> {
+        if id.slot >= rules.max_gotchis_per_identity {
+            return Err(format!(
+                "Gotchi slot {} is out of range, an identity can own at most {} gotchis",
+                id.slot, rules.max_gotchis_per_identity
+            ));
+        }
+        Ok(())
+    }
+
+    /// Rejects acting on the empty leaf of `id`.
+    fn check_exists(&self, id: &GotchiId) -> Result<(), String> {
+        if self.name.is_empty() {
+            return Err(format!("Gotchi {id} does not exist"));
+        }
+        Ok(())
+    }
+
+    /// Rejects nameless gotchis, which would be taken for empty leaves.
+    fn check_name(name: &str) -> Result<(), String> {
+        if name.is_empty() {
+            return Err("A gotchi needs a name".to_string());
+        }
+        Ok(())
+    }
+
+    /// Rejects giving a gotchi the leaf of `id` when it is taken.
+    fn check_vacant(&self, id: &GotchiId) -> Result<(), String> {
+        if !self.name.is_empty() {
+            return Err(format!("Gotchi {id} already exists"));
+        }
+        Ok(())
     }
 
-    pub fn random_sick(&mut self, rng: &mut impl Rng, block_height: u64) {
+    pub fn is_sick_with(&self, disease: Disease) -> bool {
+        matches!(self.health, HyliGotchiHealth::Sick(d, _) if d == disease)
+    }
+
+    pub fn is_asleep(&self) -> bool {
+        self.activity == HyliGotchiActivity::Sleeping
+    }
+
+    /// Babies sleep twice as long as older gotchis.
+    pub fn sleep_duration(&self, rules: &GameRules) -> u64 {
+        match self.stage {
+            LifeStage::Baby => rules.sleep_duration.saturating_mul(2),
+            _ => rules.sleep_duration,
+        }
+    }
+
+    /// Wakes a sleeping gotchi up once it has slept long enough.
+    fn wake_if_rested(&mut self, block_height: u64) {
+        if self.is_asleep() && self.wakes_at <= block_height {
+            self.activity = HyliGotchiActivity::Idle;
+        }
+    }
+
+    /// A gotchi is happy with at least three quarters of the maximum happiness.
+    pub fn is_happy(&self, rules: &GameRules) -> bool {
+        self.happiness.saturating_mul(4) >= rules.max_happiness.saturating_mul(3)
+    }
+
+    /// Moves the gotchi to the life stage of its age, and ends its life past `lifespan`.
+    fn grow(&mut self, rules: &GameRules, block_height: u64) {
+        let age = block_height.saturating_sub(self.born_at);
+        let stage = LifeStage::at_age(age, rules);
+        if stage != self.stage {
+            self.stage = stage;
+            self.stage_since = block_height;
+        }
+
+        // Natural end of life, whatever the health of the gotchi.
+        if rules.lifespan != 0 && age >= rules.lifespan {
+            self.die(block_height);
+        }
+    }
+
+    pub fn is_hungry(&self, rules: &GameRules) -> bool {
+        self.food < rules.max_food
+            || self.sweets < rules.max_sweets
+            || self.vitamins < rules.max_vitamins
+    }
+
+    pub fn random_sick(&mut self, rng: &mut impl Rng, rules: &GameRules, block_height: u64) {
+        let underfed = self.food < rules.max_food / 2 || self.sweets < rules.max_sweets / 2;
+        let lacks_vitamins = self.vitamins < rules.max_vitamins / 2;
+        let filthy = self.cleanliness(rules) == 0;
+        let obese = self.is_obese(rules);
         if matches!(self.health, HyliGotchiHealth::Healthy)
-            && (self.food < MAX_FOOD / 2 || self.sweets < MAX_SWEETS / 2)
+            && (underfed || lacks_vitamins || filthy || obese)
         {
-            // If the gotchi has less than half of the maximum food, sweets, or vitamins,
-            // it becomes sick
-            if rng.random_range(0..=1) == 0 {
-                // Randomly decide if the gotchi becomes sick
-                self.health = HyliGotchiHealth::Sick(block_height);
+            // If the gotchi has less than half of the maximum food, sweets or vitamins, lives
+            // among as many poops as can pile up, or is obese, it can become sick
+            if one_in(rng, self.sick_odds(rules)) {
+                // The disease depends on what the gotchi lacks the most
+                let disease = if filthy {
+                    Disease::Infection
+                } else if lacks_vitamins {
+                    Disease::Cold
+                } else {
+                    Disease::Indigestion
+                };
+                self.health = HyliGotchiHealth::Sick(disease, block_height);
             }
         }
     }
 
-    fn random_death(&mut self, rng: &mut SipRng, block_height: u64) {
-        if let HyliGotchiHealth::Sick(since) = self.health {
-            if block_height - since > 50_000 && rng.random_range(0..=1) == 0 {
-                // If the gotchi has been sick for more than 50.000 blocks, it has a chance to die
-                self.health = HyliGotchiHealth::Dead;
-                self.death_count += 1;
+    fn random_death(&mut self, rng: &mut SipRng, rules: &GameRules, block_height: u64) {
+        if let HyliGotchiHealth::Sick(_, since) = self.health {
+            // A disease caught at a tick can be stamped ahead of the decay steps, which may
+            // also have been shortened by a migration since.
+            if block_height.saturating_sub(since) > rules.death_window
+                && one_in(rng, rules.death_odds)
+            {
+                // Sick for longer than the death window, the gotchi has a chance to die
+                self.die(block_height);
             }
         }
     }
-    fn resurrect(&mut self, block_height: u64) {
-        // Resurrect the gotchi if it has been dead for more than 200 blocks
-        if matches!(self.health, HyliGotchiHealth::Dead) && block_height - self.born_at > 100 {
-            self.health = HyliGotchiHealth::Healthy;
-            self.born_at = block_height;
-            self.pooped = false;
-            self.food = MAX_FOOD;
-            self.sweets = MAX_SWEETS;
-            self.vitamins = MAX_VITAMINS;
-            self.last_food_block_height = block_height;
-            self.last_sweets_at = block_height;
-            self.last_vitamins_at = block_height;
-            self.activity = HyliGotchiActivity::Idle;
+    fn die(&mut self, block_height: u64) {
+        self.health = HyliGotchiHealth::Dead;
+        self.death_count += 1;
+        self.died_at = block_height;
+    }
+
+    /// Tokens to pay to resurrect the gotchi, growing with each death.
+    pub fn resurrect_cost(&self, rules: &GameRules) -> u64 {
+        rules.resurrect_cost.saturating_mul(self.death_count)
+    }
+
+    /// The gotchi is born again as an egg, keeping its name, genome and death count.
+    fn resurrect(&mut self, rules: &GameRules, block_height: u64) {
+        self.health = HyliGotchiHealth::Healthy;
+        self.born_at = block_height;
+        self.poops = 0;
+        self.food = rules.max_food;
+        self.sweets = rules.max_sweets;
+        self.vitamins = rules.max_vitamins;
+        self.happiness = rules.max_happiness;
+        self.weight = rules.normal_weight;
+        self.last_food_block_height = block_height;
+        self.last_sweets_at = block_height;
+        self.last_vitamins_at = block_height;
+        self.last_decay_at = block_height;
+        self.activity = HyliGotchiActivity::Idle;
+        self.stage = LifeStage::Egg;
+        self.stage_since = block_height;
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Default,
+)]
+pub enum LifeStage {
+    #[default]
+    Egg,
+    Baby,
+    Teen,
+    Adult,
+    Elder,
+}
+
+impl LifeStage {
+    pub fn at_age(age: u64, rules: &GameRules) -> LifeStage {
+        if age >= rules.elder_age {
+            LifeStage::Elder
+        } else if age >= rules.adult_age {
+            LifeStage::Adult
+        } else if age >= rules.teen_age {
+            LifeStage::Teen
+        } else if age >= rules.hatch_age {
+            LifeStage::Baby
+        } else {
+            LifeStage::Egg
         }
     }
 }
 
+impl Display for LifeStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                LifeStage::Egg => "Egg",
+                LifeStage::Baby => "Baby",
+                LifeStage::Teen => "Teen",
+                LifeStage::Adult => "Adult",
+                LifeStage::Elder => "Elder",
+            },
+        )
+    }
+}
+
 #[derive(
     Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Default,
 )]
@@ -412,6 +1342,7 @@ pub enum HyliGotchiActivity {
     #[default]
     Idle,
     Playing,
+    Sleeping,
 }
 
 #[derive(
@@ -420,10 +1351,47 @@ pub enum HyliGotchiActivity {
 pub enum HyliGotchiHealth {
     #[default]
     Healthy,
-    Sick(u64), // Block height when the gotchi got sick
+    Sick(Disease, u64), // Block height when the gotchi got sick
     Dead,
 }
 
+/// Illness of a sick gotchi, each with its own remedy.
+#[derive(
+    Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
+pub enum Disease {
+    /// Caught by a gotchi lacking vitamins, cured by filling its vitamins up.
+    Cold,
+    /// Caught by an underfed gotchi, cured by medicine.
+    Indigestion,
+    /// Caught by a gotchi living among its poops, cured by cleaning.
+    Infection,
+}
+
+impl Disease {
+    /// Whether the disease can be passed on to other gotchis.
+    pub fn is_contagious(&self) -> bool {
+        match self {
+            Disease::Cold | Disease::Infection => true,
+            Disease::Indigestion => false,
+        }
+    }
+}
+
+impl Display for Disease {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Disease::Cold => "cold",
+                Disease::Indigestion => "indigestion",
+                Disease::Infection => "infection",
+            },
+        )
+    }
+}
+
 impl Display for HyliGotchiHealth {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -431,7 +1399,9 @@ impl Display for HyliGotchiHealth {
             "{}",
             match self {
                 HyliGotchiHealth::Healthy => "Healthy".to_string(),
-                HyliGotchiHealth::Sick(since) => format!("Sick since block {since}"),
+                HyliGotchiHealth::Sick(disease, since) => {
+                    format!("Sick with {disease} since block {since}")
+                }
                 HyliGotchiHealth::Dead => "Dead".to_string(),
             },
         )
@@ -446,6 +1416,7 @@ impl Display for HyliGotchiActivity {
             match self {
                 HyliGotchiActivity::Idle => "Idle",
                 HyliGotchiActivity::Playing => "Playing",
+                HyliGotchiActivity::Sleeping => "Sleeping",
             },
         )
     }
@@ -477,19 +1448,111 @@ pub enum HyliGotchiEvent {
     },
 }
 
+/// Identifies one of the gotchis owned by an identity.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GotchiId {
+    pub owner: Identity,
+    pub slot: u32,
+}
+
+impl GotchiId {
+    pub fn new(owner: Identity, slot: u32) -> Self {
+        GotchiId { owner, slot }
+    }
+}
+
+impl Display for GotchiId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}#{}", self.owner.0, self.slot)
+    }
+}
+
 /// Enum representing possible calls to the contract functions.
+#[serde_with::serde_as]
 #[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
 pub enum HyliGotchiAction {
-    Init(Identity, String),
-    FeedFood(Identity, u64),
-    FeedSweets(Identity, u64),
-    FeedVitamins(Identity, u64),
-    CleanPoop(Identity, u128),
-    Resurrect(Identity, u128),
+    Init(GotchiId, String),
+    FeedFood(GotchiId, u64),
+    FeedSweets(GotchiId, u64),
+    FeedVitamins(GotchiId, u64),
+    /// Gives medicine to a gotchi suffering from indigestion.
+    Heal(GotchiId, u64),
+    CleanPoop(GotchiId, u128),
+    /// Pays the given amount of the resurrection token to bring a dead gotchi back.
+    Resurrect(GotchiId, u64),
+    Play(GotchiId, u128),
+    Sleep(GotchiId, u128),
+    Wake(GotchiId, u128),
+    /// Offers a gotchi to another identity, which takes it with `Adopt`.
+    Transfer(GotchiId, Identity),
+    /// Moves a gotchi offered to the caller (first id) into one of the caller's slots (second id).
+    Adopt(GotchiId, GotchiId),
+    /// Lets the owner of another gotchi (second id) breed it with the caller's gotchi (first id),
+    /// for as long as the second id holds that same gotchi.
+    AllowBreeding(GotchiId, GotchiId),
+    /// Breeds the caller's gotchi (first id) with a partner (second id), giving birth to a new
+    /// gotchi named after the string in one of the caller's slots (third id).
+    Breed(GotchiId, GotchiId, GotchiId, String),
     Tick(u128),
+    RotateBackendKey(u128, #[serde_as(as = "[_; 33]")] BackendPubKey),
+    /// Wipes every gotchi from the world.
+    ResetWorld(u128),
+    /// Switches the world to new game rules, keeping its gotchis as they are.
+    /// This is not a schema migration: leaves keep their encoding, so a change to the layout
+    /// of `HyliGotchi` requires redeploying the contract.
+    Migrate(u128, GameRules),
 }
 
 impl HyliGotchiAction {
+    /// The gotchi owned by the caller once a player action succeeds, `None` for backend actions.
+    pub fn gotchi_id(&self) -> Option<&GotchiId> {
+        match self {
+            HyliGotchiAction::Init(id, _)
+            | HyliGotchiAction::FeedFood(id, _)
+            | HyliGotchiAction::FeedSweets(id, _)
+            | HyliGotchiAction::FeedVitamins(id, _)
+            | HyliGotchiAction::Heal(id, _)
+            | HyliGotchiAction::CleanPoop(id, _)
+            | HyliGotchiAction::Resurrect(id, _)
+            | HyliGotchiAction::Play(id, _)
+            | HyliGotchiAction::Sleep(id, _)
+            | HyliGotchiAction::Wake(id, _)
+            | HyliGotchiAction::Transfer(id, _)
+            | HyliGotchiAction::Adopt(_, id)
+            | HyliGotchiAction::AllowBreeding(id, _)
+            | HyliGotchiAction::Breed(_, _, id, _) => Some(id),
+            HyliGotchiAction::Tick(..)
+            | HyliGotchiAction::RotateBackendKey(..)
+            | HyliGotchiAction::ResetWorld(..)
+            | HyliGotchiAction::Migrate(..) => None,
+        }
+    }
+
+    /// Leaves touched by a player action, empty for backend actions.
+    pub fn gotchi_ids(&self) -> Vec<GotchiId> {
+        match self {
+            HyliGotchiAction::Adopt(from, to) => vec![from.clone(), to.clone()],
+            HyliGotchiAction::AllowBreeding(id, partner) if partner != id => {
+                vec![id.clone(), partner.clone()]
+            }
+            HyliGotchiAction::Breed(parent, partner, child, _) => {
+                vec![parent.clone(), partner.clone(), child.clone()]
+            }
+            _ => self.gotchi_id().cloned().into_iter().collect(),
+        }
+    }
+
+    /// Rejects actions touching the same gotchi more than once.
+    pub fn check_distinct_gotchis(&self) -> Result<(), String> {
+        let ids = self.gotchi_ids();
+        for (i, id) in ids.iter().enumerate() {
+            if ids[..i].contains(id) {
+                return Err(format!("Gotchi {id} is given more than once"));
+            }
+        }
+        Ok(())
+    }
+
     pub fn as_blob(&self, contract_name: sdk::ContractName) -> sdk::Blob {
         sdk::Blob {
             contract_name,
@@ -511,20 +1574,85 @@ impl HyliGotchi {
         borsh::to_vec(self)
     }
 
-    fn resurrect_gotchi(&mut self, block_height: u64) -> Result<String, String> {
+    fn resurrect_gotchi(&mut self, rules: &GameRules, block_height: u64) -> Result<String, String> {
         if self.health != HyliGotchiHealth::Dead {
             return Err(format!(
                 "Gotchi {} is not dead and cannot be resurrected",
                 self.name
             ));
         }
+        if rules.max_deaths != 0 && self.death_count >= rules.max_deaths {
+            return Err(format!(
+                "Gotchi {} died {} times and cannot be resurrected anymore",
+                self.name, self.death_count
+            ));
+        }
+        if block_height < self.died_at + rules.resurrect_delay {
+            return Err(format!(
+                "Gotchi {} cannot be resurrected before block {}",
+                self.name,
+                self.died_at + rules.resurrect_delay
+            ));
+        }
 
-        self.resurrect(block_height);
+        self.resurrect(rules, block_height);
 
         Ok(format!("Gotchi {} has been resurrected", self.name))
     }
 
-    fn clean_poop(&mut self, _block_hash: &sdk::ConsensusProposalHash) -> Result<String, String> {
+    fn play(&mut self, rules: &GameRules) -> Result<String, String> {
+        if self.health == HyliGotchiHealth::Dead {
+            return Err(format!("Gotchi {} is dead and cannot play", self.name));
+        }
+        if self.is_asleep() {
+            return Err(format!("Gotchi {} is asleep and cannot play", self.name));
+        }
+
+        self.activity = HyliGotchiActivity::Playing;
+        self.happiness = self
+            .happiness
+            .saturating_add(rules.play_happiness)
+            .min(rules.max_happiness);
+
+        Ok(format!(
+            "Gotchi {} played. New happiness level: {}",
+            self.name, self.happiness
+        ))
+    }
+
+    fn sleep(&mut self, rules: &GameRules, block_height: u64) -> Result<String, String> {
+        if self.health == HyliGotchiHealth::Dead {
+            return Err(format!("Gotchi {} is dead and cannot sleep", self.name));
+        }
+        if self.is_asleep() {
+            return Err(format!("Gotchi {} is already asleep", self.name));
+        }
+
+        self.activity = HyliGotchiActivity::Sleeping;
+        self.wakes_at = block_height + self.sleep_duration(rules);
+
+        Ok(format!(
+            "Gotchi {} fell asleep until block {}",
+            self.name, self.wakes_at
+        ))
+    }
+
+    fn wake(&mut self) -> Result<String, String> {
+        if !self.is_asleep() {
+            return Err(format!("Gotchi {} is not asleep", self.name));
+        }
+
+        self.activity = HyliGotchiActivity::Idle;
+
+        Ok(format!("Gotchi {} woke up", self.name))
+    }
+
+    /// Cleaning makes the gotchi happier by one point per poop removed.
+    fn clean_poop(
+        &mut self,
+        rules: &GameRules,
+        _block_hash: &sdk::ConsensusProposalHash,
+    ) -> Result<String, String> {
         if self.health == HyliGotchiHealth::Dead {
             return Err(format!(
                 "Gotchi {} is dead and cannot be cleaned",
@@ -532,19 +1660,60 @@ impl HyliGotchi {
             ));
         }
 
-        if !self.pooped {
+        if self.poops == 0 {
             return Err(format!("Gotchi {} has no poop to clean", self.name));
         }
 
-        self.pooped = false;
+        let poops = core::mem::take(&mut self.poops);
+        self.happiness = self
+            .happiness
+            .saturating_add(poops)
+            .min(rules.max_happiness);
+
+        if self.is_sick_with(Disease::Infection) {
+            self.health = HyliGotchiHealth::Healthy;
+            return Ok(format!(
+                "Gotchi {} cleaned up {} poops and recovered from its infection",
+                self.name, poops
+            ));
+        }
+
+        Ok(format!("Gotchi {} cleaned up {} poops", self.name, poops))
+    }
 
-        Ok(format!("Gotchi {} cleaned up poop", self.name))
+    /// Give medicine to the gotchi, which only cures indigestion
+    fn heal(&mut self, medicine_amount: u64, rules: &GameRules) -> Result<String, String> {
+        match self.health {
+            HyliGotchiHealth::Dead => {
+                Err(format!("Gotchi {} is dead and cannot be healed", self.name))
+            }
+            HyliGotchiHealth::Healthy => Err(format!(
+                "Gotchi {} is healthy and needs no medicine",
+                self.name
+            )),
+            HyliGotchiHealth::Sick(Disease::Indigestion, _) => {
+                if medicine_amount == 0 {
+                    return Err(format!("Gotchi {} needs some medicine", self.name));
+                }
+                self.check_appetite(Nutrient::Medicine, medicine_amount, rules)?;
+                self.health = HyliGotchiHealth::Healthy;
+                Ok(format!(
+                    "Gotchi {} took medicine and recovered from its indigestion",
+                    self.name
+                ))
+            }
+            HyliGotchiHealth::Sick(disease, _) => Err(format!(
+                "Medicine does not cure the {disease} of gotchi {}",
+                self.name
+            )),
+        }
     }
 
     /// Feed vitamins to the gotchi
     fn feed_vitamins(
         &mut self,
         vitamins_amount: u64,
+        rules: &GameRules,
         block_height: u64,
         _block_hash: &sdk::ConsensusProposalHash,
     ) -> Result<String, String> {
@@ -554,17 +1723,24 @@ impl HyliGotchi {
                 self.name
             ));
         }
+        if self.is_asleep() {
+            return Err(format!(
+                "Gotchi {} is asleep and cannot be fed vitamins",
+                self.name
+            ));
+        }
+        self.check_appetite(Nutrient::Vitamins, vitamins_amount, rules)?;
 
-        self.vitamins = self
-            .vitamins
-            .saturating_add(vitamins_amount)
-            .min(MAX_VITAMINS);
+        self.vitamins = self.vitamins.saturating_add(vitamins_amount);
         self.last_vitamins_at = block_height;
 
-        if self.vitamins == MAX_VITAMINS && matches!(self.health, HyliGotchiHealth::Sick(_)) {
-            // If the gotchi has full vitamins, it recovers from sickness
+        if self.vitamins == rules.max_vitamins && self.is_sick_with(Disease::Cold) {
+            // If the gotchi has full vitamins, it recovers from its cold
             self.health = HyliGotchiHealth::Healthy;
             self.vitamins = 0;
+        } else if self.vitamins == rules.max_vitamins && self.health == HyliGotchiHealth::Healthy {
+            // A healthy gotchi with full vitamins is vaccinated
+            self.vaccinated_until = block_height + rules.vaccine_duration;
         }
 
         Ok(format!(
@@ -577,6 +1753,7 @@ impl HyliGotchi {
     fn feed_sweets(
         &mut self,
         sweets_amount: u64,
+        rules: &GameRules,
         block_height: u64,
         _block_hash: &sdk::ConsensusProposalHash,
     ) -> Result<String, String> {
@@ -586,9 +1763,21 @@ impl HyliGotchi {
                 self.name
             ));
         }
+        if self.is_asleep() {
+            return Err(format!(
+                "Gotchi {} is asleep and cannot be fed sweets",
+                self.name
+            ));
+        }
+        self.check_appetite(Nutrient::Sweets, sweets_amount, rules)?;
 
-        self.sweets = self.sweets.saturating_add(sweets_amount).min(MAX_SWEETS);
+        self.sweets = self.sweets.saturating_add(sweets_amount);
+        self.weight = self.weight.saturating_add(sweets_amount);
         self.last_sweets_at = block_height;
+        self.happiness = self
+            .happiness
+            .saturating_add(sweets_amount)
+            .min(rules.max_happiness);
 
         Ok(format!(
             "Gotchi {} fed {} sweets. New sweets level: {}",
@@ -600,6 +1789,7 @@ impl HyliGotchi {
     fn feed_food(
         &mut self,
         food_amount: u64,
+        rules: &GameRules,
         block_height: u64,
         _block_hash: &sdk::ConsensusProposalHash,
     ) -> Result<String, String> {
@@ -609,8 +1799,15 @@ impl HyliGotchi {
                 self.name
             ));
         }
+        if self.is_asleep() {
+            return Err(format!(
+                "Gotchi {} is asleep and cannot be fed food",
+                self.name
+            ));
+        }
+        self.check_appetite(Nutrient::Food, food_amount, rules)?;
 
-        self.food = self.food.saturating_add(food_amount).min(MAX_FOOD);
+        self.food = self.food.saturating_add(food_amount);
         self.last_food_block_height = block_height;
 
         Ok(format!(
@@ -622,6 +1819,8 @@ impl HyliGotchi {
     fn new_gotchi(
         &mut self,
         name: String,
+        key: &H256,
+        rules: &GameRules,
         blockhash: &BlockHash,
         block_height: u64,
     ) -> Result<String, String> {
@@ -629,7 +1828,13 @@ impl HyliGotchi {
             return Err(format!("Gotchi already exists for user {}", self.name));
         }
 
-        *self = HyliGotchi::new(name.clone(), block_height);
+        *self = HyliGotchi::new(name.clone(), rules, block_height);
+
+        // The genome is drawn from the block hash and the gotchi key, which carries its owner.
+        let mut hash = SipHasher::new();
+        hash.write(blockhash.0.as_bytes());
+        hash.write(key.as_slice());
+        self.genome = Genome::random(&mut SipRng::seed_from_u64(hash.finish()));
 
         Ok(format!(
             "New gotchi {name} created with blockhash {blockhash}",
@@ -638,15 +1843,43 @@ impl HyliGotchi {
     }
 }
 
-fn check_tick_commitment(
+/// Data the backend signs to authorize a tick.
+pub fn tick_signed_data(nonce: u128) -> Vec<u8> {
+    let mut data_to_sign = nonce.to_le_bytes().to_vec();
+    data_to_sign.extend_from_slice("HyliGotchiWorldTick".as_bytes());
+    data_to_sign
+}
+
+/// Data the backend signs to hand its role over to `new_key`.
+pub fn rotate_backend_key_signed_data(nonce: u128, new_key: &BackendPubKey) -> Vec<u8> {
+    let mut data_to_sign = nonce.to_le_bytes().to_vec();
+    data_to_sign.extend_from_slice(new_key);
+    data_to_sign.extend_from_slice("HyliGotchiWorldRotateBackendKey".as_bytes());
+    data_to_sign
+}
+
+/// Data the backend signs to wipe every gotchi from the world.
+pub fn reset_world_signed_data(nonce: u128) -> Vec<u8> {
+    let mut data_to_sign = nonce.to_le_bytes().to_vec();
+    data_to_sign.extend_from_slice("HyliGotchiWorldReset".as_bytes());
+    data_to_sign
+}
+
+/// Data the backend signs to switch the world to new game rules.
+pub fn migrate_signed_data(nonce: u128, rules: &GameRules) -> Vec<u8> {
+    let mut data_to_sign = nonce.to_le_bytes().to_vec();
+    data_to_sign.extend_from_slice(&borsh::to_vec(rules).expect("Failed to encode GameRules"));
+    data_to_sign.extend_from_slice("HyliGotchiWorldMigrate".as_bytes());
+    data_to_sign
+}
+
+fn check_backend_signature(
     calldata: &sdk::Calldata,
-    nonce: u128,
+    data_to_sign: &[u8],
     backend_pubkey: &BackendPubKey,
 ) -> Result<(), String> {
-    let mut data_to_sign = nonce.to_le_bytes().to_vec();
-    data_to_sign.extend_from_slice("HyliGotchiWorldTick".as_bytes());
     // Check if the calldata contains a secp256k1 blob with the expected data
-    let blob = CheckSecp256k1::new(calldata, &data_to_sign)
+    let blob = CheckSecp256k1::new(calldata, data_to_sign)
         .with_blob_index(BlobIndex(0))
         .expect()?;
     if blob.public_key != *backend_pubkey {
@@ -655,90 +1888,248 @@ fn check_tick_commitment(
     Ok(())
 }
 
-// Permissioned and run only on the handler.
-#[cfg(feature = "client")]
-impl crate::client::HyliGotchiWorld {
-    pub fn tick(
-        &mut self,
-        block_hash: &sdk::ConsensusProposalHash,
-        block_height: u64,
-    ) -> Result<String, String> {
-        info!(
-            current_timestamp = block_height,
-            block_hash = %block_hash.0,
-            "Processing tick"
-        );
+/// Backend-signed actions carry a strictly increasing nonce, so they cannot be replayed.
+fn check_backend_nonce(nonce: u128, last_backend_nonce: u128) -> Result<(), String> {
+    if nonce <= last_backend_nonce {
+        return Err(format!(
+            "Stale backend nonce {nonce}, last accepted nonce is {last_backend_nonce}"
+        ));
+    }
+    Ok(())
+}
+
+/// Lets contagious diseases spread between the given gotchis, which must be every gotchi of the
+/// world. Only the gotchis that catch a disease are brought up to date and reseeded, the others
+/// are left as they were last written. Returns the indices, in key order, of the changed ones.
+pub fn tick_gotchis(
+    gotchis: &mut [(H256, HyliGotchi)],
+    rules: &GameRules,
+    block_hash: &BlockHash,
+    block_height: u64,
+) -> Vec<usize> {
+    // Neighbours are taken in key order, which does not depend on who builds the tick.
+    gotchis.sort_unstable_by_key(|(key, _)| *key);
+
+    let infected = spread_diseases(gotchis, rules, block_height);
+
+    for &i in &infected {
+        gotchis[i].1.reseed(block_hash);
+    }
+    infected
+}
+
+/// Each gotchi may catch a contagious disease from its previous or next gotchi in key order.
+/// Diseases caught during this tick are not passed on before the next one.
+fn spread_diseases(
+    gotchis: &mut [(H256, HyliGotchi)],
+    rules: &GameRules,
+    block_height: u64,
+) -> Vec<usize> {
+    // Only gotchis last written sick are contagious, a gotchi falling sick on its own being
+    // seen so once written again. They are brought up to date, on a copy, to check they still are.
+    let contagious = gotchis
+        .iter()
+        .map(|(key, gotchi)| {
+            if !matches!(gotchi.health, HyliGotchiHealth::Sick(..)) {
+                return None;
+            }
+            let mut gotchi = gotchi.clone();
+            gotchi.catch_up(key, rules, block_height);
+            match gotchi.health {
+                HyliGotchiHealth::Sick(disease, _) if disease.is_contagious() => Some(disease),
+                _ => None,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut infected = Vec::new();
+    for (i, (key, gotchi)) in gotchis.iter_mut().enumerate() {
+        if gotchi.name.is_empty() || gotchi.vaccinated_until > block_height {
+            continue;
+        }
+
+        let mut hash = SipHasher::new();
+        hash.write(key.as_slice());
+        hash.write(&block_height.to_le_bytes());
+        hash.write("contagion".as_bytes());
+        let mut rng = SipRng::seed_from_u64(hash.finish());
+
+        let neighbours = [i.checked_sub(1), Some(i + 1)];
+        let Some(disease) = neighbours
+            .into_iter()
+            .flatten()
+            .filter_map(|j| contagious.get(j).copied().flatten())
+            .find(|_| one_in(&mut rng, rules.contagion_odds))
+        else {
+            continue;
+        };
+
+        // Only a gotchi about to catch the disease is brought up to date, to check its health.
+        let mut caught_up = gotchi.clone();
+        caught_up.catch_up(key, rules, block_height);
+        if caught_up.health == HyliGotchiHealth::Healthy {
+            caught_up.health = HyliGotchiHealth::Sick(disease, block_height);
+            *gotchi = caught_up;
+            infected.push(i);
+        }
+    }
+    infected
+}
+
+impl HyliGotchi {
+    /// Replays every decay step elapsed since `last_decay_at`. Each step is seeded from the
+    /// gotchi key, its `decay_seed` and the step height only, so the result does not depend
+    /// on when or how often the gotchi is read.
+    pub fn catch_up(&mut self, key: &H256, rules: &GameRules, block_height: u64) {
+        if self.name.is_empty() || rules.decay_interval == 0 {
+            return;
+        }
+
+        while self.health != HyliGotchiHealth::Dead
+            && self.last_decay_at + rules.decay_interval <= block_height
+        {
+            self.last_decay_at += rules.decay_interval;
+
+            let mut hash = SipHasher::new();
+            hash.write(key.as_slice());
+            hash.write(&self.decay_seed.to_le_bytes());
+            hash.write(&self.last_decay_at.to_le_bytes());
+            let mut rng = SipRng::seed_from_u64(hash.finish());
 
-        //if block_height - self.last_block_height < 2 {
-        //    return Err("Tick too soon, please wait".to_string());
-        //}
+            self.decay(&mut rng, rules, self.last_decay_at);
+        }
+
+        self.wake_if_rested(block_height);
+    }
 
+    /// Draws a new `decay_seed` from the block writing the gotchi.
+    fn reseed(&mut self, block_hash: &BlockHash) {
+        if self.name.is_empty() {
+            return;
+        }
         let mut hash = SipHasher::new();
-        hash.write(block_hash.0.as_ref());
-        let seed = hash.finish();
+        hash.write(block_hash.0.as_bytes());
+        self.decay_seed = hash.finish();
+    }
 
-        let mut rng = SipRng::seed_from_u64(seed);
+    fn decay(&mut self, rng: &mut SipRng, rules: &GameRules, block_height: u64) {
+        self.grow(rules, block_height);
+        if self.health == HyliGotchiHealth::Dead || self.stage == LifeStage::Egg {
+            // Neither dead gotchis nor eggs have needs.
+            return;
+        }
 
-        let mut keys = self
-            .gotchis
-            .0
-            .store()
-            .leaves_map()
-            .keys()
-            .cloned()
-            .collect::<Vec<_>>();
-        keys.sort(); // Need deterministic ordering.
+        self.wake_if_rested(block_height);
+        let asleep = self.is_asleep();
 
-        for key in keys {
-            let Ok(mut gotchi) = self.gotchis.0.get(&key) else {
-                continue;
+        // Simulate some random activity, obese gotchis playing half as often
+        if !asleep {
+            let playing = match self.is_obese(rules) {
+                true => one_in(rng, 4),
+                false => rng.random_range(0..=1) == 1,
             };
-            info!("gotchi: {} {:?}", gotchi.name, gotchi);
-            // Simulate some random activity
-            gotchi.activity = if rng.random_range(0..=1) == 0 {
-                HyliGotchiActivity::Idle
-            } else {
+            self.activity = if playing {
                 HyliGotchiActivity::Playing
+            } else {
+                HyliGotchiActivity::Idle
             };
+        }
+
+        if self.activity == HyliGotchiActivity::Playing && self.weight > rules.normal_weight {
+            // Playing burns the extra weight
+            self.weight -= 1;
+        }
 
-            if gotchi.last_food_block_height + 1 < block_height {
-                // time to decrease food points
-                let food_decrease = rng.random_range(0..=1);
-                gotchi.food = gotchi.food.saturating_sub(food_decrease);
-                gotchi.last_food_block_height = block_height;
+        if self.last_food_block_height + 1 < block_height {
+            // time to decrease food points
+            let mut food_decrease = self.genome.metabolism().decrease(rng);
+            if self.stage == LifeStage::Baby {
+                // Babies eat more
+                food_decrease += self.genome.metabolism().decrease(rng);
+            }
+            if asleep && rng.random_bool(0.5) {
+                // A sleeping gotchi gets hungry half as fast
+                food_decrease = 0;
             }
+            self.food = self.food.saturating_sub(food_decrease);
+            self.last_food_block_height = block_height;
 
-            if gotchi.last_sweets_at + 1 < block_height {
-                // time to decrease sweets points
-                let sweets_decrease = rng.random_range(0..=1);
-                gotchi.sweets = gotchi.sweets.saturating_sub(sweets_decrease);
-                gotchi.last_sweets_at = block_height;
+            if self.food == 0 {
+                // A starving gotchi loses weight
+                self.weight = self.weight.saturating_sub(1);
             }
+        }
 
-            if gotchi.vitamins == MAX_VITAMINS && matches!(gotchi.health, HyliGotchiHealth::Sick(_))
-            {
-                // If the gotchi has full vitamins, it recovers from sickness
-                gotchi.health = HyliGotchiHealth::Healthy;
-                gotchi.vitamins = 0;
+        if self.last_sweets_at + 1 < block_height {
+            // time to decrease sweets points
+            let mut sweets_decrease = self.genome.metabolism().decrease(rng);
+            if asleep && rng.random_bool(0.5) {
+                sweets_decrease = 0;
             }
+            self.sweets = self.sweets.saturating_sub(sweets_decrease);
+            self.last_sweets_at = block_height;
+        }
 
-            if !gotchi.pooped && rng.random_range(0..=10) == 0 {
-                // Randomly decide if the gotchi poops
-                gotchi.pooped = true;
+        if self.last_vitamins_at + 1 < block_height {
+            // Vitamins wear off slowly, a point every other step on average
+            if rng.random_bool(0.5) {
+                self.vitamins = self.vitamins.saturating_sub(1);
             }
+            self.last_vitamins_at = block_height;
+        }
+
+        // Happiness fades without attention, but not in dreams
+        if !asleep {
+            self.happiness = self.happiness.saturating_sub(rng.random_range(0..=1));
+        }
+
+        if self.vitamins == rules.max_vitamins && self.is_sick_with(Disease::Cold) {
+            // If the gotchi has full vitamins, it recovers from its cold
+            self.health = HyliGotchiHealth::Healthy;
+            self.vitamins = 0;
+        }
+
+        let poop_odds = match asleep {
+            true => rules.poop_odds.saturating_mul(2),
+            false => rules.poop_odds,
+        };
+        if self.poops < rules.max_poops && one_in(rng, poop_odds) {
+            // Randomly decide if the gotchi poops
+            self.poops += 1;
+        }
 
-            gotchi.random_sick(&mut rng, block_height);
+        self.random_sick(rng, rules, block_height);
 
-            gotchi.random_death(&mut rng, block_height);
+        self.random_death(rng, rules, block_height);
+    }
+}
 
+// Permissioned and run only on the handler.
+#[cfg(feature = "client")]
+impl crate::client::HyliGotchiWorld {
+    pub fn tick(
+        &mut self,
+        block_hash: &sdk::ConsensusProposalHash,
+        block_height: u64,
+    ) -> Result<String, String> {
+        info!(
+            current_timestamp = block_height,
+            block_hash = %block_hash.0,
+            "Processing tick"
+        );
+
+        let mut gotchis = self.sorted_gotchis();
+
+        let infected = tick_gotchis(&mut gotchis, &self.rules, block_hash, block_height);
+
+        for i in infected {
+            let (key, gotchi) = gotchis[i].clone();
             self.gotchis
                 .0
                 .update(key, gotchi)
                 .map_err(|e| format!("Failed to update gotchi: {e}"))?;
         }
 
-        info!("Random: {}", rng.random::<u8>());
-
         Ok(format!(
             "Tick processed successfully. Block hash: {}, Timestamp: {}, world state updated. {}",
             block_hash.0, block_height, self
@@ -1,10 +1,10 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use sdk::{merkle_utils::SHA256Hasher, Identity};
+use sdk::merkle_utils::SHA256Hasher;
 use serde::ser::{Serialize, SerializeSeq, Serializer};
 use sha2::{Digest, Sha256};
 use sparse_merkle_tree::{default_store::DefaultStore, traits::Value, SparseMerkleTree, H256};
 
-use crate::HyliGotchi;
+use crate::{GotchiId, HyliGotchi};
 
 #[derive(Debug, Default)]
 pub struct HyliGotchiWorldSMT(
@@ -68,9 +68,12 @@ impl BorshDeserialize for HyliGotchiWorldSMT {
 }
 
 impl HyliGotchi {
-    pub fn compute_key(owner: &Identity) -> H256 {
+    /// The owner is length-prefixed, so that no owner and slot pair can collide with another.
+    pub fn compute_key(id: &GotchiId) -> H256 {
         let mut hasher = Sha256::new();
-        hasher.update(owner.0.as_bytes());
+        hasher.update((id.owner.0.len() as u64).to_le_bytes());
+        hasher.update(id.owner.0.as_bytes());
+        hasher.update(id.slot.to_le_bytes());
         let result = hasher.finalize();
         let mut h = [0u8; 32];
         h.copy_from_slice(&result);
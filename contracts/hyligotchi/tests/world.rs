@@ -0,0 +1,718 @@
+use client_sdk::transaction_builder::TxExecutorHandler;
+use hyle_smt_token::SmtTokenAction;
+use hyligotchi::{
+    client::{
+        AuthHeaders, HyliGotchiWorld, HyliGotchiWorldConstructor, SESSION_KEY_VALIDITY_BLOCKS,
+    },
+    migrate_signed_data, reset_world_signed_data, tick_gotchis, tick_signed_data, Disease,
+    GameRules, GotchiId, HyliGotchi, HyliGotchiAction, HyliGotchiHealth, HyliGotchiWorldZkView,
+    LifeStage, Nutrient,
+};
+use sdk::{
+    verifiers::Secp256k1Blob, Blob, BlobIndex, BlobTransaction, BlockHeight, Calldata,
+    ConsensusProposalHash, ContractAction, ContractName, Hashed, Identity, TxContext, ZkContract,
+};
+use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+use sha2::{Digest, Sha256};
+use sparse_merkle_tree::H256;
+
+const CONTRACT: &str = "hyligotchi";
+
+type Tx = (Identity, Vec<Blob>);
+
+/// A world run both by the handler, as the indexer and the prover do, and by the contract,
+/// as the zkVM does, checking after each transaction that both agree on the state.
+struct TestWorld {
+    world: HyliGotchiWorld,
+    backend_key: SecretKey,
+    backend_nonce: u128,
+}
+
+impl TestWorld {
+    fn new(rules: GameRules) -> Self {
+        let backend_key = SecretKey::from_byte_array([7; 32]).expect("valid secret key");
+        let backend_pubkey =
+            PublicKey::from_secret_key(&Secp256k1::new(), &backend_key).serialize();
+        TestWorld {
+            world: HyliGotchiWorld::new(&HyliGotchiWorldConstructor {
+                backend_pubkey,
+                rules,
+            }),
+            backend_key,
+            backend_nonce: 0,
+        }
+    }
+
+    fn run(&mut self, tx: Tx, block_height: u64) -> Result<String, String> {
+        self.run_batch(vec![tx], block_height).remove(0)
+    }
+
+    /// Runs the transactions of a block in a single proof, merging their commitment
+    /// metadata as the prover does, and returns the result of each.
+    fn run_batch(&mut self, txs: Vec<Tx>, block_height: u64) -> Vec<Result<String, String>> {
+        let tx_ctx = TxContext {
+            block_hash: ConsensusProposalHash(format!("block-{block_height}")),
+            block_height: BlockHeight(block_height),
+            ..Default::default()
+        };
+
+        let mut metadata: Option<Vec<u8>> = None;
+        let mut handled = vec![];
+        for (identity, blobs) in txs {
+            // The action blob comes last, after the blobs it relies on.
+            let index = blobs.len() - 1;
+            let next = self
+                .world
+                .build_commitment_metadata(&blobs[index])
+                .expect("Failed to build commitment metadata");
+            metadata = Some(match metadata {
+                None => next,
+                Some(initial) => self
+                    .world
+                    .merge_commitment_metadata(initial, next)
+                    .expect("Failed to merge commitment metadata"),
+            });
+
+            let calldata = Calldata {
+                identity: identity.clone(),
+                tx_hash: BlobTransaction::new(identity, blobs.clone()).hashed(),
+                tx_blob_count: blobs.len(),
+                blobs: blobs.into(),
+                index: BlobIndex(index),
+                tx_ctx: Some(tx_ctx.clone()),
+                private_input: vec![],
+            };
+            let output = self
+                .world
+                .handle(&calldata)
+                .expect("Failed to handle transaction");
+            assert_eq!(output.next_state, self.world.get_state_commitment());
+            handled.push((calldata, output));
+        }
+
+        let mut view: HyliGotchiWorldZkView =
+            borsh::from_slice(&metadata.expect("No transaction to run"))
+                .expect("Failed to decode commitment metadata");
+        handled
+            .into_iter()
+            .map(|(calldata, output)| {
+                assert_eq!(view.commit(), output.initial_state);
+                let res = view.execute(&calldata);
+                assert_eq!(res.is_ok(), output.success, "Result mismatch: {res:?}");
+                assert_eq!(view.commit(), output.next_state, "State mismatch: {res:?}");
+                res.map(|(res, ..)| String::from_utf8(res).expect("Result is not utf-8"))
+            })
+            .collect()
+    }
+
+    /// An action signed by the backend.
+    fn backend_tx(&self, data_to_sign: &[u8], action: HyliGotchiAction) -> Tx {
+        let identity = Identity::new("hyligotchi_server@secp256k1");
+        let secp_blob = secp256k1_blob(&identity, &self.backend_key, data_to_sign);
+        (identity, vec![secp_blob, action.as_blob(contract_name())])
+    }
+
+    fn tick_tx(&mut self) -> Tx {
+        self.backend_nonce += 1;
+        self.backend_tx(
+            &tick_signed_data(self.backend_nonce),
+            HyliGotchiAction::Tick(self.backend_nonce),
+        )
+    }
+
+    fn tick(&mut self, block_height: u64) -> Result<String, String> {
+        let tx = self.tick_tx();
+        self.run(tx, block_height)
+    }
+
+    fn reset_world_tx(&mut self) -> Tx {
+        self.backend_nonce += 1;
+        self.backend_tx(
+            &reset_world_signed_data(self.backend_nonce),
+            HyliGotchiAction::ResetWorld(self.backend_nonce),
+        )
+    }
+
+    fn migrate_tx(&mut self, rules: &GameRules) -> Tx {
+        self.backend_nonce += 1;
+        self.backend_tx(
+            &migrate_signed_data(self.backend_nonce, rules),
+            HyliGotchiAction::Migrate(self.backend_nonce, rules.clone()),
+        )
+    }
+
+    /// The gotchi as the contract will see it at `block_height`.
+    fn caught_up(&self, id: &GotchiId, block_height: u64) -> HyliGotchi {
+        let mut gotchi = self.world.get(id).expect("Gotchi is missing");
+        gotchi.catch_up(
+            &HyliGotchi::compute_key(id),
+            &self.world.rules,
+            block_height,
+        );
+        gotchi
+    }
+
+    fn init(&mut self, id: &GotchiId, name: &str, block_height: u64) {
+        self.run(
+            player_tx(HyliGotchiAction::Init(id.clone(), name.to_string()), vec![]),
+            block_height,
+        )
+        .expect("Failed to init gotchi");
+    }
+}
+
+fn contract_name() -> ContractName {
+    ContractName(CONTRACT.to_string())
+}
+
+/// An action of the owner of its gotchi, after the blobs it relies on.
+fn player_tx(action: HyliGotchiAction, mut blobs: Vec<Blob>) -> Tx {
+    let identity = action
+        .gotchi_id()
+        .expect("Not a player action")
+        .owner
+        .clone();
+    blobs.push(action.as_blob(contract_name()));
+    (identity, blobs)
+}
+
+/// A signature of `data` by `key` on behalf of `identity`.
+fn secp256k1_blob(identity: &Identity, key: &SecretKey, data: &[u8]) -> Blob {
+    let secp = Secp256k1::new();
+    let message_hash: [u8; 32] = Sha256::digest(data).into();
+    let signature = secp.sign_ecdsa(Message::from_digest(message_hash), key);
+    Secp256k1Blob::new(
+        identity.clone(),
+        data,
+        &PublicKey::from_secret_key(&secp, key).to_string(),
+        &signature.to_string(),
+    )
+    .expect("Failed to build secp256k1 blob")
+    .as_blob()
+}
+
+/// Rules without randomness in health, with gotchis growing up within a few decay steps.
+fn calm_rules() -> GameRules {
+    GameRules {
+        decay_interval: 10,
+        hatch_age: 10,
+        teen_age: 20,
+        adult_age: 30,
+        elder_age: 10_000,
+        lifespan: 0,
+        poop_odds: 0,
+        sick_odds: 0,
+        death_odds: 0,
+        contagion_odds: 0,
+        mutation_odds: 0,
+        ..GameRules::default()
+    }
+}
+
+/// A transfer of `amount` tokens from `sender` to the contract.
+fn transfer_blob(token: &str, sender: &Identity, amount: u128) -> Blob {
+    SmtTokenAction::Transfer {
+        sender: sender.clone(),
+        recipient: Identity::new(CONTRACT),
+        amount,
+    }
+    .as_blob(ContractName(token.to_string()), None, None)
+}
+
+fn alice(slot: u32) -> GotchiId {
+    GotchiId::new(Identity::new("alice@wallet"), slot)
+}
+
+fn bob(slot: u32) -> GotchiId {
+    GotchiId::new(Identity::new("bob@wallet"), slot)
+}
+
+fn carol(slot: u32) -> GotchiId {
+    GotchiId::new(Identity::new("carol@wallet"), slot)
+}
+
+#[test]
+fn tick_matches_contract() {
+    let mut world = TestWorld::new(GameRules::default());
+    // Nothing to tick in an empty world.
+    world.tick(10).expect("Failed to tick an empty world");
+
+    world.init(&alice(0), "Pixel", 20);
+    world.init(&alice(1), "Byte", 30);
+    world.init(&bob(0), "Nibble", 40);
+    // Far enough apart for gotchis to hatch, grow up, get hungry, sick and maybe die, each
+    // written again in between so that their sickness can spread.
+    for block_height in [50_000, 100_000, 400_000] {
+        for id in [alice(0), alice(1), bob(0)] {
+            let withdraw = HyliGotchiAction::Transfer(id.clone(), id.owner);
+            let _ = world.run(player_tx(withdraw, vec![]), block_height - 1);
+        }
+        world.tick(block_height).expect("Failed to tick");
+    }
+    assert_eq!(world.world.gotchi_count(), 3);
+}
+
+#[test]
+fn tick_leaves_healthy_worlds_untouched() {
+    let mut world = TestWorld::new(GameRules::default());
+    world.init(&alice(0), "Pixel", 10);
+    world.init(&bob(0), "Nibble", 20);
+    let before = borsh::to_vec(&world.world.gotchis).unwrap();
+
+    world.tick(400_000).expect("Failed to tick");
+    // Gotchis are only brought up to date when read or acted upon.
+    let after = borsh::to_vec(&world.world.gotchis).unwrap();
+    assert_eq!(before, after);
+}
+
+#[test]
+fn tick_batched_with_player_actions_matches_contract() {
+    let mut world = TestWorld::new(GameRules::default());
+    world.init(&alice(0), "Pixel", 10);
+
+    let txs = vec![
+        world.tick_tx(),
+        player_tx(HyliGotchiAction::Init(bob(0), "Nibble".to_string()), vec![]),
+        world.tick_tx(),
+    ];
+    for res in world.run_batch(txs, 50_000) {
+        res.expect("Failed to run batched transaction");
+    }
+    assert_eq!(world.world.gotchi_count(), 2);
+}
+
+#[test]
+fn tick_rejects_a_replayed_nonce() {
+    let mut world = TestWorld::new(GameRules::default());
+    world.init(&alice(0), "Pixel", 10);
+    world.tick(100).expect("Failed to tick");
+
+    let replayed = world.backend_tx(&tick_signed_data(1), HyliGotchiAction::Tick(1));
+    let err = world.run(replayed, 200).unwrap_err();
+    assert!(err.contains("Stale backend nonce"), "{err}");
+}
+
+#[test]
+fn catch_up_does_not_depend_on_how_often_it_runs() {
+    let rules = GameRules::default();
+    let key = HyliGotchi::compute_key(&alice(0));
+    let mut once = HyliGotchi::new("Pixel".to_string(), &rules, 0);
+    once.decay_seed = 42;
+    let mut several = once.clone();
+
+    once.catch_up(&key, &rules, 500_000);
+    for block_height in [1, 3_600, 3_601, 100_000, 250_000, 500_000, 500_000] {
+        several.catch_up(&key, &rules, block_height);
+    }
+    assert_eq!(
+        borsh::to_vec(&once).unwrap(),
+        borsh::to_vec(&several).unwrap()
+    );
+}
+
+#[test]
+fn migrate_matches_contract() {
+    let mut world = TestWorld::new(GameRules::default());
+    world.init(&alice(0), "Pixel", 10);
+
+    let rules = GameRules {
+        max_food: 20,
+        ..GameRules::default()
+    };
+    let tx = world.migrate_tx(&rules);
+    world.run(tx, 20).expect("Failed to migrate");
+    assert_eq!(world.world.rules, rules);
+    // Gotchis are kept as they are.
+    let gotchi = world.world.get(&alice(0)).expect("Gotchi is gone");
+    assert_eq!((gotchi.name.as_str(), gotchi.food), ("Pixel", 10));
+
+    let replayed = world.backend_tx(
+        &migrate_signed_data(1, &GameRules::default()),
+        HyliGotchiAction::Migrate(1, GameRules::default()),
+    );
+    let err = world.run(replayed, 30).unwrap_err();
+    assert!(err.contains("Stale backend nonce"), "{err}");
+    assert_eq!(world.world.rules, rules);
+}
+
+#[test]
+fn batched_migrates_match_contract() {
+    let mut world = TestWorld::new(GameRules::default());
+    world.init(&alice(0), "Pixel", 10);
+
+    let bigger = GameRules {
+        max_food: 20,
+        ..GameRules::default()
+    };
+    let smaller = GameRules {
+        max_food: 15,
+        max_gotchis_per_identity: 1,
+        ..GameRules::default()
+    };
+    let txs = vec![
+        world.migrate_tx(&bigger),
+        player_tx(HyliGotchiAction::Init(bob(0), "Nibble".to_string()), vec![]),
+        world.migrate_tx(&smaller),
+        // Out of range once the second migration has run.
+        player_tx(HyliGotchiAction::Init(bob(1), "Byte".to_string()), vec![]),
+        world.tick_tx(),
+    ];
+    let res = world.run_batch(txs, 20);
+    assert!(res[0].is_ok() && res[1].is_ok() && res[2].is_ok() && res[4].is_ok());
+    assert!(res[3].is_err());
+
+    assert_eq!(world.world.rules, smaller);
+    // Born under the rules of the first migration.
+    assert_eq!(world.world.get(&bob(0)).map(|gotchi| gotchi.food), Some(20));
+    assert_eq!(world.world.gotchi_count(), 2);
+}
+
+#[test]
+fn adopt_matches_contract() {
+    let mut world = TestWorld::new(calm_rules());
+    world.init(&alice(0), "Pixel", 1);
+
+    // Nobody can adopt a gotchi that was not offered.
+    let adopt = || player_tx(HyliGotchiAction::Adopt(alice(0), bob(1)), vec![]);
+    assert!(world.run(adopt(), 2).is_err());
+
+    let offer = HyliGotchiAction::Transfer(alice(0), bob(0).owner);
+    world
+        .run(player_tx(offer, vec![]), 3)
+        .expect("Failed to offer");
+    world.run(adopt(), 4).expect("Failed to adopt");
+
+    assert!(world
+        .world
+        .get(&alice(0))
+        .is_none_or(|gotchi| gotchi.name.is_empty()));
+    let adopted = world.world.get(&bob(1)).expect("Adopted gotchi is missing");
+    assert_eq!(adopted.name, "Pixel");
+    assert_eq!(adopted.pending_owner, None);
+    assert_eq!(world.world.gotchi_count(), 1);
+}
+
+#[test]
+fn breed_matches_contract() {
+    let mut world = TestWorld::new(calm_rules());
+    world.init(&alice(0), "Pixel", 1);
+    world.init(&bob(0), "Nibble", 1);
+
+    let breed = |slot| {
+        player_tx(
+            HyliGotchiAction::Breed(alice(0), bob(0), alice(slot), "Kid".to_string()),
+            vec![],
+        )
+    };
+    // Babies cannot breed.
+    assert!(world.run(breed(1), 15).is_err());
+    // Adults need the consent of the owner of the partner.
+    assert!(world.run(breed(1), 40).is_err());
+
+    let allow = player_tx(HyliGotchiAction::AllowBreeding(bob(0), alice(0)), vec![]);
+    let res = world.run_batch(vec![allow, breed(1)], 50);
+    assert!(res.iter().all(Result::is_ok), "{res:?}");
+
+    let child = world.world.get(&alice(1)).expect("Offspring is missing");
+    assert_eq!((child.name.as_str(), child.born_at), ("Kid", 50));
+    assert_eq!(child.stage, LifeStage::Egg);
+    // The consent is used up by the birth.
+    let partner = world.world.get(&bob(0)).expect("Partner is missing");
+    assert_eq!(partner.breeding_partner, None);
+    assert!(world.run(breed(2), 60).is_err());
+    assert_eq!(world.world.gotchi_count(), 3);
+}
+
+#[test]
+fn breeding_consent_does_not_pass_on_to_the_next_gotchi_of_a_slot() {
+    let mut world = TestWorld::new(calm_rules());
+    world.init(&alice(0), "Pixel", 1);
+    world.init(&bob(0), "Nibble", 1);
+
+    let breed = |name: &str| {
+        player_tx(
+            HyliGotchiAction::Breed(alice(0), bob(0), alice(1), name.to_string()),
+            vec![],
+        )
+    };
+    let allow = player_tx(HyliGotchiAction::AllowBreeding(bob(0), alice(0)), vec![]);
+    world.run(allow, 40).expect("Failed to allow breeding");
+    // A nameless offspring would be an empty leaf, and the consent would be used up for nothing.
+    let err = world.run(breed(""), 40).unwrap_err();
+    assert!(err.contains("needs a name"), "{err}");
+    let partner = world.world.get(&bob(0)).expect("Partner is missing");
+    assert!(partner.breeding_partner.is_some());
+
+    // Pixel leaves the slot the consent was given to, and another gotchi takes it.
+    let offer = HyliGotchiAction::Transfer(alice(0), carol(0).owner);
+    world
+        .run(player_tx(offer, vec![]), 41)
+        .expect("Failed to offer");
+    let adopt = HyliGotchiAction::Adopt(alice(0), carol(0));
+    world
+        .run(player_tx(adopt, vec![]), 41)
+        .expect("Failed to adopt");
+    let nameless = HyliGotchiAction::Init(alice(0), String::new());
+    assert!(world.run(player_tx(nameless, vec![]), 41).is_err());
+    world.init(&alice(0), "Byte", 41);
+
+    let err = world.run(breed("Kid"), 80).unwrap_err();
+    assert!(err.contains("is not allowed to breed"), "{err}");
+    assert_eq!(world.world.gotchi_count(), 3);
+}
+
+#[test]
+fn heal_takes_only_the_medicine_it_needs() {
+    let owner = alice(0).owner;
+    let mut world = TestWorld::new(calm_rules());
+    world.init(&alice(0), "Pixel", 1);
+    // Calm rules never make a gotchi sick on its own.
+    let mut gotchi = world.world.get(&alice(0)).expect("Gotchi is missing");
+    gotchi.health = HyliGotchiHealth::Sick(Disease::Indigestion, 1);
+    world
+        .world
+        .gotchis
+        .0
+        .update(HyliGotchi::compute_key(&alice(0)), gotchi)
+        .expect("Failed to make the gotchi sick");
+
+    let heal = |amount: u64| {
+        player_tx(
+            HyliGotchiAction::Heal(alice(0), amount),
+            vec![transfer_blob("medicine", &owner, amount as u128)],
+        )
+    };
+    let err = world.run(heal(2), 2).unwrap_err();
+    assert!(err.contains("can only take 1 more medicine"), "{err}");
+    world.run(heal(1), 2).expect("Failed to heal");
+    let gotchi = world.world.get(&alice(0)).expect("Gotchi is missing");
+    assert_eq!(gotchi.health, HyliGotchiHealth::Healthy);
+    assert!(world.run(heal(1), 3).is_err());
+}
+
+#[test]
+fn contagion_spreads_to_healthy_neighbours_only() {
+    let rules = GameRules {
+        // No decay, so that health only changes through contagion.
+        decay_interval: 0,
+        contagion_odds: 1,
+        ..GameRules::default()
+    };
+    let healths = [
+        HyliGotchiHealth::Healthy,
+        HyliGotchiHealth::Sick(Disease::Cold, 0),
+        HyliGotchiHealth::Healthy,
+        // Neighbour of a gotchi that only caught a disease during this tick.
+        HyliGotchiHealth::Healthy,
+        // Vaccinated below.
+        HyliGotchiHealth::Healthy,
+        HyliGotchiHealth::Sick(Disease::Infection, 0),
+        HyliGotchiHealth::Sick(Disease::Indigestion, 0),
+        // Neighbour of a gotchi with a disease that is not contagious.
+        HyliGotchiHealth::Healthy,
+    ];
+    let mut gotchis = healths
+        .into_iter()
+        .enumerate()
+        .map(|(i, health)| {
+            let mut gotchi = HyliGotchi::new(format!("Gotchi {i}"), &rules, 0);
+            gotchi.health = health;
+            (H256::from([i as u8; 32]), gotchi)
+        })
+        .collect::<Vec<_>>();
+    gotchis[4].1.vaccinated_until = 1_000;
+
+    let infected = tick_gotchis(&mut gotchis, &rules, &ConsensusProposalHash::default(), 100);
+    assert_eq!(infected, [0, 2]);
+
+    let healths = gotchis
+        .iter()
+        .map(|(_, gotchi)| gotchi.health.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        healths,
+        [
+            HyliGotchiHealth::Sick(Disease::Cold, 100),
+            HyliGotchiHealth::Sick(Disease::Cold, 0),
+            HyliGotchiHealth::Sick(Disease::Cold, 100),
+            HyliGotchiHealth::Healthy,
+            HyliGotchiHealth::Healthy,
+            HyliGotchiHealth::Sick(Disease::Infection, 0),
+            HyliGotchiHealth::Sick(Disease::Indigestion, 0),
+            HyliGotchiHealth::Healthy,
+        ]
+    );
+}
+
+#[test]
+fn sickness_stamped_ahead_of_decay_steps_does_not_kill() {
+    let rules = GameRules {
+        death_window: 50,
+        death_odds: 1,
+        ..calm_rules()
+    };
+    let key = HyliGotchi::compute_key(&alice(0));
+    let mut gotchi = HyliGotchi::new("Pixel".to_string(), &rules, 0);
+    // Caught at a tick, while the gotchi had not decayed since its birth.
+    gotchi.health = HyliGotchiHealth::Sick(Disease::Infection, 1_000);
+
+    gotchi.catch_up(&key, &rules, 1_000);
+    assert_eq!(
+        gotchi.health,
+        HyliGotchiHealth::Sick(Disease::Infection, 1_000)
+    );
+    gotchi.catch_up(&key, &rules, 1_060);
+    assert_eq!(gotchi.health, HyliGotchiHealth::Dead);
+}
+
+#[test]
+fn resurrect_respects_cooldown_and_cost() {
+    let rules = GameRules {
+        lifespan: 100,
+        resurrect_delay: 50,
+        resurrect_cost: 2,
+        ..calm_rules()
+    };
+    let token = rules.resurrect_token.0.clone();
+    let owner = alice(0).owner;
+    let mut world = TestWorld::new(rules.clone());
+    world.init(&alice(0), "Pixel", 1);
+
+    let resurrect = |amount: u64, paid: u128| {
+        player_tx(
+            HyliGotchiAction::Resurrect(alice(0), amount),
+            vec![transfer_blob(&token, &owner, paid)],
+        )
+    };
+    // Died of old age at block 101, it cannot come back before block 151.
+    let err = world.run(resurrect(2, 2), 120).unwrap_err();
+    assert!(
+        err.contains("cannot be resurrected before block 151"),
+        "{err}"
+    );
+    // The cost grows with the number of deaths, and must be paid in full.
+    assert!(world.run(resurrect(1, 1), 160).is_err());
+    assert!(world.run(resurrect(2, 1), 160).is_err());
+    world
+        .run(resurrect(2, 2), 160)
+        .expect("Failed to resurrect");
+
+    let gotchi = world.world.get(&alice(0)).expect("Gotchi is missing");
+    assert_eq!(gotchi.health, HyliGotchiHealth::Healthy);
+    assert_eq!((gotchi.born_at, gotchi.death_count), (160, 1));
+
+    // Died again at block 260, now costing twice as much.
+    assert!(world.run(resurrect(2, 2), 310).is_err());
+    world
+        .run(resurrect(4, 4), 310)
+        .expect("Failed to resurrect");
+    let gotchi = world.world.get(&alice(0)).expect("Gotchi is missing");
+    assert_eq!(gotchi.death_count, 2);
+    assert_eq!(gotchi.resurrect_cost(&rules), 4);
+}
+
+#[test]
+fn feeding_more_than_the_appetite_is_rejected() {
+    let rules = calm_rules();
+    let owner = alice(0).owner;
+    let mut world = TestWorld::new(rules.clone());
+    world.init(&alice(0), "Pixel", 1);
+
+    let feed = |amount: u64| {
+        player_tx(
+            HyliGotchiAction::FeedFood(alice(0), amount),
+            vec![transfer_blob("oranj", &owner, amount as u128)],
+        )
+    };
+    // A newborn gotchi is full.
+    let err = world.run(feed(1), 2).unwrap_err();
+    assert!(err.contains("can only take 0 more food"), "{err}");
+
+    let appetite = world
+        .caught_up(&alice(0), 400)
+        .appetite(Nutrient::Food, &rules)
+        .expect("Food is limited");
+    assert!(appetite > 0);
+    assert!(world.run(feed(appetite + 1), 400).is_err());
+    world.run(feed(appetite), 400).expect("Failed to feed");
+    let gotchi = world.world.get(&alice(0)).expect("Gotchi is missing");
+    assert_eq!(gotchi.food, rules.max_food);
+}
+
+#[test]
+fn vitamins_wear_off_so_that_gotchis_can_be_vaccinated() {
+    let rules = calm_rules();
+    let owner = alice(0).owner;
+    let mut world = TestWorld::new(rules.clone());
+    world.init(&alice(0), "Pixel", 1);
+    // Full vitamins at birth do not vaccinate.
+    let gotchi = world.world.get(&alice(0)).expect("Gotchi is missing");
+    assert_eq!(gotchi.vaccinated_until, 0);
+
+    let missing = world
+        .caught_up(&alice(0), 400)
+        .appetite(Nutrient::Vitamins, &rules)
+        .expect("Vitamins are limited");
+    assert!(missing > 0, "Vitamins never wear off");
+    let feed = player_tx(
+        HyliGotchiAction::FeedVitamins(alice(0), missing),
+        vec![transfer_blob("vitamin", &owner, missing as u128)],
+    );
+    world.run(feed, 400).expect("Failed to feed vitamins");
+
+    let gotchi = world.world.get(&alice(0)).expect("Gotchi is missing");
+    assert_eq!(gotchi.vitamins, rules.max_vitamins);
+    assert_eq!(gotchi.vaccinated_until, 400 + rules.vaccine_duration);
+}
+
+#[test]
+fn session_keys_stop_granting_reads_once_unused_or_reset() {
+    let mut world = TestWorld::new(calm_rules());
+    let session_key = SecretKey::from_byte_array([9; 32]).expect("valid secret key");
+    let owner = alice(0).owner;
+    let auth = AuthHeaders {
+        identity: owner.0.clone(),
+        public_key: PublicKey::from_secret_key(&Secp256k1::new(), &session_key).serialize(),
+    };
+    let signed = |action: HyliGotchiAction| {
+        player_tx(
+            action,
+            vec![secp256k1_blob(&owner, &session_key, b"session")],
+        )
+    };
+
+    world
+        .run(signed(HyliGotchiAction::Init(alice(0), "Pixel".into())), 10)
+        .expect("Failed to init gotchi");
+    assert!(
+        auth.check_session_key(&world.world).is_ok(),
+        "A key that just signed can read"
+    );
+
+    // A key the wallet no longer accepts signs nothing more, and is forgotten.
+    let expiry = 10 + SESSION_KEY_VALIDITY_BLOCKS;
+    world.tick(expiry).expect("Failed to tick");
+    assert!(
+        auth.check_session_key(&world.world).is_ok(),
+        "A key is valid until the end of its window"
+    );
+    world.tick(expiry + 1).expect("Failed to tick");
+    assert!(auth.check_session_key(&world.world).is_err());
+    assert!(world.world.session_keys.is_empty());
+
+    world
+        .run(
+            signed(HyliGotchiAction::Transfer(alice(0), owner.clone())),
+            expiry + 2,
+        )
+        .expect("Failed to withdraw");
+    assert!(
+        auth.check_session_key(&world.world).is_ok(),
+        "A key that signs again can read again"
+    );
+
+    let reset = world.reset_world_tx();
+    world.run(reset, expiry + 3).expect("Failed to reset world");
+    assert!(auth.check_session_key(&world.world).is_err());
+    assert!(world.world.session_keys.is_empty());
+}
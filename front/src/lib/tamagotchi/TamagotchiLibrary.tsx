@@ -1,6 +1,7 @@
 import React, { useState, useRef, useCallback } from 'react';
 import MiniTamagotchi from './components/MiniTamagotchi';
 import FullTamagotchi from './components/FullTamagotchi';
+import type { AuthSigner } from './api/client';
 
 export interface TamagotchiLibraryProps {
   enabled: boolean;
@@ -40,6 +41,7 @@ export interface TamagotchiLibraryProps {
   
   // Advanced wallet functions
   createIdentityBlobs?: () => [any, any]; // Function to create signed identity blobs
+  signAuth?: AuthSigner; // Signs API auth messages with the session key of createIdentityBlobs
 }
 
 type TransitionState = 'mini' | 'expanding' | 'full' | 'collapsing';
@@ -72,7 +74,8 @@ const TamagotchiLibrary: React.FC<TamagotchiLibraryProps> = ({
   useAPI = true,
   apiUrl,
   indexerUrl,
-  createIdentityBlobs
+  createIdentityBlobs,
+  signAuth
 }) => {
   const [transitionState, setTransitionState] = useState<TransitionState>('mini');
   const [transitionData, setTransitionData] = useState<TransitionData | null>(null);
@@ -152,6 +155,7 @@ const TamagotchiLibrary: React.FC<TamagotchiLibraryProps> = ({
           apiUrl={apiUrl}
           indexerUrl={indexerUrl}
           createIdentityBlobs={createIdentityBlobs}
+          signAuth={signAuth}
         />
       )}
     </>
@@ -5,7 +5,7 @@ export interface ApiGotchi {
   food: number;
   sweets: number;
   vitamins: number;
-  pooped?: boolean; // Whether the pet has pooped
+  poops?: number; // Number of poops waiting to be cleaned
   born_at?: number; // Block number when the pet was born
 }
 
@@ -14,9 +14,14 @@ export interface ApiResponse {
   tx_hash: string;
 }
 
+// Signs a message with the session key of the wallet, returning hex-encoded
+// compressed public key and compact signature over the sha256 of the message.
+export type AuthSigner = (message: string) => { publicKey: string; signature: string };
+
 class HyligotchiAPIClient {
   private baseUrl: string;
   private identity: string | null = null;
+  private authSigner: AuthSigner | null = null;
   private lastTxHash: string | null = null;
 
   constructor() {
@@ -27,6 +32,10 @@ class HyligotchiAPIClient {
     this.identity = identity;
   }
 
+  setAuthSigner(signer: AuthSigner) {
+    this.authSigner = signer;
+  }
+
   setBaseUrl(url: string) {
     this.baseUrl = url;
   }
@@ -35,16 +44,32 @@ class HyligotchiAPIClient {
     return this.lastTxHash;
   }
 
+  // Identity headers, signed with the session key so that the server can trust them.
+  private authHeaders(): Record<string, string> {
+    const headers: Record<string, string> = {};
+
+    if (this.identity) {
+      headers['x-identity'] = this.identity;
+
+      if (this.authSigner) {
+        const timestamp = Date.now().toString();
+        const { publicKey, signature } = this.authSigner(`HyliGotchiAuth:${this.identity}:${timestamp}`);
+        headers['x-timestamp'] = timestamp;
+        headers['x-public-key'] = publicKey;
+        headers['x-signature'] = signature;
+      }
+    }
+
+    return headers;
+  }
+
   private async fetchWithAuth(url: string, options: RequestInit = {}) {
     const headers: HeadersInit = {
       'Content-Type': 'application/json',
       ...options.headers,
+      ...this.authHeaders(),
     };
 
-    if (this.identity) {
-      (headers as any)['x-identity'] = this.identity;
-    }
-
     const fetchOptions = {
       ...options,
       headers,
@@ -100,7 +125,7 @@ class HyligotchiAPIClient {
     console.log('Sending body:', body);
     console.log('Body length:', body.length);
     
-    const response: ApiResponse = await this.fetchWithAuth(`/api/init?name=${encodeURIComponent(name)}`, {
+    const response: ApiResponse = await this.fetchWithAuth(`/api/init?wait=true&name=${encodeURIComponent(name)}`, {
       method: 'POST',
       body: body,
     });
@@ -113,9 +138,7 @@ class HyligotchiAPIClient {
   async getState(): Promise<ApiGotchi | null> {
     try {
       const response = await fetch(`${this.baseUrl}/v1/indexer/contract/hyligotchi2/state`, {
-        headers: {
-          'X-identity': this.identity || '',
-        },
+        headers: this.authHeaders(),
       });
 
       if (!response.ok) {
@@ -144,7 +167,7 @@ class HyligotchiAPIClient {
       throw new Error('Failed to create identity blobs. Please ensure you have an active session key.');
     }
     
-    const response: ApiResponse = await this.fetchWithAuth(`/api/feed/food?amount=${amount}`, {
+    const response: ApiResponse = await this.fetchWithAuth(`/api/feed/food?wait=true&amount=${amount}`, {
       method: 'POST',
       body: JSON.stringify(blobs),
     });
@@ -167,7 +190,7 @@ class HyligotchiAPIClient {
       throw new Error('Failed to create identity blobs. Please ensure you have an active session key.');
     }
     
-    const response: ApiResponse = await this.fetchWithAuth(`/api/feed/sweets?amount=${amount}`, {
+    const response: ApiResponse = await this.fetchWithAuth(`/api/feed/sweets?wait=true&amount=${amount}`, {
       method: 'POST',
       body: JSON.stringify(blobs),
     });
@@ -190,7 +213,7 @@ class HyligotchiAPIClient {
       throw new Error('Failed to create identity blobs. Please ensure you have an active session key.');
     }
     
-    const response: ApiResponse = await this.fetchWithAuth(`/api/feed/vitamins?amount=${amount}`, {
+    const response: ApiResponse = await this.fetchWithAuth(`/api/feed/vitamins?wait=true&amount=${amount}`, {
       method: 'POST',
       body: JSON.stringify(blobs),
     });
@@ -213,7 +236,7 @@ class HyligotchiAPIClient {
       throw new Error('Failed to create identity blobs. Please ensure you have an active session key.');
     }
     
-    const response: ApiResponse = await this.fetchWithAuth('/api/resurrect', {
+    const response: ApiResponse = await this.fetchWithAuth('/api/resurrect?wait=true', {
       method: 'POST',
       body: JSON.stringify(blobs),
     });
@@ -236,7 +259,7 @@ class HyligotchiAPIClient {
       throw new Error('Failed to create identity blobs. Please ensure you have an active session key.');
     }
     
-    const response: ApiResponse = await this.fetchWithAuth('/api/poop/clean', {
+    const response: ApiResponse = await this.fetchWithAuth('/api/poop/clean?wait=true', {
       method: 'POST',
       body: JSON.stringify(blobs),
     });
@@ -18,7 +18,7 @@ import { useTamagotchiState } from '../hooks/useTamagotchiState';
 import { useMenuState } from '../hooks/useMenuState';
 import { useFoodBalances } from '../hooks/useFoodBalances';
 import { useHealthBalances } from '../hooks/useHealthBalances';
-import { apiClient } from '../api/client';
+import { apiClient, type AuthSigner } from '../api/client';
 import { apiResponseToGameState, parseHealthStatus } from '../utils/gameStateManager';
 import { WalletProvider } from '../contexts/WalletContext';
 import TransactionNotification from './ui/TransactionNotification';
@@ -59,6 +59,7 @@ interface FullTamagotchiProps {
   apiUrl?: string;
   indexerUrl?: string;
   createIdentityBlobs?: () => [any, any];
+  signAuth?: AuthSigner;
 }
 
 const FullTamagotchi: React.FC<FullTamagotchiProps> = ({
@@ -75,7 +76,8 @@ const FullTamagotchi: React.FC<FullTamagotchiProps> = ({
   useAPI = true,
   apiUrl,
   indexerUrl,
-  createIdentityBlobs
+  createIdentityBlobs,
+  signAuth
 }) => {
   const imgRef = useRef<HTMLImageElement>(null);
   const tutorialRef = useRef<TutorialScreenRef>(null);
@@ -120,11 +122,14 @@ const FullTamagotchi: React.FC<FullTamagotchiProps> = ({
     if (useAPI && identity) {
       console.log('Setting API client identity:', identity);
       apiClient.setIdentity(identity);
+      if (signAuth) {
+        apiClient.setAuthSigner(signAuth);
+      }
       if (apiUrl) {
         apiClient.setBaseUrl(apiUrl);
       }
     }
-  }, [identity, useAPI, apiUrl]);
+  }, [identity, useAPI, apiUrl, signAuth]);
 
   // Use custom hooks for state management (without tutorial state)
   const {
@@ -167,10 +172,10 @@ const FullTamagotchi: React.FC<FullTamagotchiProps> = ({
 
   // Helper function to update poo state from API response
   const updatePooState = (apiGotchi: any) => {
-    if (apiGotchi.pooped !== undefined) {
-      console.log('Updating poo state from API:', apiGotchi.pooped);
-      setShowPoo(apiGotchi.pooped);
-      setNeedsCleaning(apiGotchi.pooped);
+    if (apiGotchi.poops !== undefined) {
+      console.log('Updating poo state from API:', apiGotchi.poops);
+      setShowPoo(apiGotchi.poops > 0);
+      setNeedsCleaning(apiGotchi.poops > 0);
     }
   };
 
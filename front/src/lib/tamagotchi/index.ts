@@ -16,6 +16,7 @@ export { useHealthBalances } from './hooks/useHealthBalances';
 export { useWalletFunctions } from './contexts/WalletContext';
 
 // Type exports
+export type { AuthSigner } from './api/client';
 export type { FoodBalances, FoodType } from './utils/foodBalances';
 export type { HealthBalances, HealthType } from './utils/healthBalances';
 
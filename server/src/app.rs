@@ -1,31 +1,48 @@
-use std::{sync::Arc, time::Duration};
+use std::{path::PathBuf, str::FromStr, sync::Arc, time::Duration};
 
-use crate::{ticker_module::create_secp256k1_blob, utils::AppError};
+use crate::{
+    ticker_module::create_secp256k1_blob,
+    tx_tracker::{TxStatus, TxTracker},
+    utils::AppError,
+};
 use anyhow::Result;
 use axum::{
     extract::{Json, Query, State},
     http::{HeaderMap, Method, StatusCode},
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     routing::{get, post},
     Router,
 };
+use borsh::BorshDeserialize;
 use client_sdk::rest_client::{NodeApiClient, NodeApiHttpClient};
 
 use axum::extract::Path;
 use hyle_modules::{
-    bus::{BusClientReceiver, SharedMessageBus},
+    bus::SharedMessageBus,
     module_bus_client, module_handle_messages,
     modules::{prover::AutoProverEvent, BuildApiContextInner, Module},
+    node_state::module::NodeStateEvent,
 };
 use hyle_smt_token::SmtTokenAction;
-use hyligotchi::{client::HyliGotchiWorld, HyliGotchi, HyliGotchiAction};
-use sdk::{Blob, BlobTransaction, ContractAction, ContractName, Identity};
+use hyligotchi::{
+    client::{decode_secp256k1_blob, AuthHeaders, HyliGotchiWorld, SECP256K1_CONTRACT},
+    migrate_signed_data, reset_world_signed_data, rotate_backend_key_signed_data, tick_signed_data,
+    GameRules, GotchiId, HyliGotchi, HyliGotchiAction, HyliGotchiHealth, Nutrient, NutrientToken,
+};
+use sdk::{
+    verifiers::Secp256k1Blob, Blob, BlobIndex, BlobTransaction, BlockHeight, Calldata,
+    ContractAction, ContractName, Identity, TxContext, TxHash,
+};
 use serde::{Deserialize, Serialize};
-use tokio::sync::Mutex;
+use tokio::sync::RwLock;
 use tower_http::cors::{Any, CorsLayer};
 
 pub struct AppModule {
     bus: AppModuleBusClient,
+    world: Arc<RwLock<Option<HyliGotchiWorld>>>,
+    /// Where the latest world is kept across restarts, until the prover sends a newer one.
+    world_file: PathBuf,
+    txs: Arc<RwLock<TxTracker>>,
 }
 
 pub struct AppModuleCtx {
@@ -33,12 +50,16 @@ pub struct AppModuleCtx {
     pub node_client: Arc<NodeApiHttpClient>,
     pub hyligotchi_cn: ContractName,
     pub crypto_context: Arc<CryptoContext>,
+    pub game_rules: GameRules,
+    pub wallet_cn: ContractName,
+    pub data_directory: PathBuf,
 }
 
 module_bus_client! {
 #[derive(Debug)]
 pub struct AppModuleBusClient {
     receiver(AutoProverEvent<HyliGotchiWorld>),
+    receiver(NodeStateEvent),
 }
 }
 
@@ -46,13 +67,19 @@ impl Module for AppModule {
     type Context = Arc<AppModuleCtx>;
 
     async fn build(bus: SharedMessageBus, ctx: Self::Context) -> Result<Self> {
+        let world_file = ctx.data_directory.join("app_world.bin");
+        let world = Arc::new(RwLock::new(Self::load_from_disk::<HyliGotchiWorld>(
+            &world_file,
+        )));
+        let txs = Arc::new(RwLock::new(TxTracker::default()));
         let state = RouterCtx {
             hyligotchi_cn: ctx.hyligotchi_cn.clone(),
-            app: Arc::new(Mutex::new(HyleOofCtx {
-                bus: bus.new_handle(),
-            })),
             client: ctx.node_client.clone(),
             crypto_context: ctx.crypto_context.clone(),
+            game_rules: Arc::new(ctx.game_rules.clone()),
+            wallet_cn: ctx.wallet_cn.clone(),
+            world: world.clone(),
+            txs: txs.clone(),
         };
 
         // Créer un middleware CORS
@@ -66,11 +93,34 @@ impl Module for AppModule {
             .route("/api/init", post(init))
             .route("/api/poop/clean", post(clean_poop))
             .route("/api/resurrect", post(resurrect))
+            .route("/api/play", post(play))
+            .route("/api/sleep", post(sleep))
+            .route("/api/wake", post(wake))
             .route("/api/feed/food", post(feed_food))
             .route("/api/feed/sweets", post(feed_sweets))
             .route("/api/feed/vitamins", post(feed_vitamins))
+            .route("/api/heal", post(heal))
+            .route("/api/gotchi/{id}/init", post(init_slot))
+            .route("/api/gotchi/{id}/poop/clean", post(clean_poop_slot))
+            .route("/api/gotchi/{id}/resurrect", post(resurrect_slot))
+            .route("/api/gotchi/{id}/play", post(play_slot))
+            .route("/api/gotchi/{id}/sleep", post(sleep_slot))
+            .route("/api/gotchi/{id}/wake", post(wake_slot))
+            .route("/api/gotchi/{id}/feed/food", post(feed_food_slot))
+            .route("/api/gotchi/{id}/feed/sweets", post(feed_sweets_slot))
+            .route("/api/gotchi/{id}/feed/vitamins", post(feed_vitamins_slot))
+            .route("/api/gotchi/{id}/heal", post(heal_slot))
+            .route("/api/gotchi/{id}/transfer", post(transfer))
+            .route("/api/gotchi/{id}/adopt", post(adopt))
+            .route("/api/gotchi/{id}/breeding/allow", post(allow_breeding))
+            .route("/api/gotchi/{id}/breed", post(breed))
+            .route("/api/gotchis", get(get_gotchis))
+            .route("/api/tx/{hash}", get(get_tx))
             .route("/api/config", get(get_config))
             .route("/api/tick/{secret}", post(trigger_tick))
+            .route("/api/admin/rotate_key/{secret}", post(rotate_backend_key))
+            .route("/api/admin/reset/{secret}", post(reset_world))
+            .route("/api/admin/migrate/{secret}", post(migrate))
             .with_state(state)
             .layer(cors); // Appliquer le middleware CORS
 
@@ -81,14 +131,47 @@ impl Module for AppModule {
         }
         let bus = AppModuleBusClient::new_from_bus(bus.new_handle()).await;
 
-        Ok(AppModule { bus })
+        Ok(AppModule {
+            bus,
+            world,
+            world_file,
+            txs,
+        })
     }
 
     async fn run(&mut self) -> Result<()> {
         module_handle_messages! {
             on_self self,
+            listen<AutoProverEvent<HyliGotchiWorld>> event => {
+                match event {
+                    AutoProverEvent::SuccessTx(tx_hash, state) => {
+                        self.txs.write().await.sequenced(&tx_hash, &state);
+                        *self.world.write().await = Some(state);
+                    }
+                    AutoProverEvent::FailedTx(tx_hash, error) => {
+                        self.txs.write().await.failed(&tx_hash, error);
+                    }
+                }
+            }
+            listen<NodeStateEvent> event => {
+                let NodeStateEvent::NewBlock(block) = event;
+                let mut txs = self.txs.write().await;
+                for tx_hash in &block.successful_txs {
+                    txs.settled(tx_hash);
+                }
+                for tx_hash in &block.failed_txs {
+                    txs.failed(tx_hash, "Transaction failed on chain".to_string());
+                }
+                for tx_hash in &block.timed_out_txs {
+                    txs.failed(tx_hash, "Transaction timed out".to_string());
+                }
+            }
         };
 
+        if let Some(world) = self.world.read().await.as_ref() {
+            Self::save_on_disk(&self.world_file, world)?;
+        }
+
         Ok(())
     }
 }
@@ -102,14 +185,14 @@ pub struct CryptoContext {
 
 #[derive(Clone)]
 pub struct RouterCtx {
-    pub app: Arc<Mutex<HyleOofCtx>>,
     pub client: Arc<NodeApiHttpClient>,
     pub hyligotchi_cn: ContractName,
     pub crypto_context: Arc<CryptoContext>,
-}
-
-pub struct HyleOofCtx {
-    pub bus: SharedMessageBus,
+    pub game_rules: Arc<GameRules>,
+    pub wallet_cn: ContractName,
+    /// State after the last transaction settled by the prover, `None` until the first one.
+    pub world: Arc<RwLock<Option<HyliGotchiWorld>>>,
+    pub txs: Arc<RwLock<TxTracker>>,
 }
 
 async fn health() -> impl IntoResponse {
@@ -120,28 +203,87 @@ async fn health() -> impl IntoResponse {
 //     Headers
 // --------------------------------------------------------
 
-const IDENTITY_HEADER: &str = "x-identity";
+/// The only wallet action a session key sends along a player action, encoded as the
+/// `WalletAction::UseSessionKey` variant of the wallet contract of
+/// https://github.com/hyli-org/wallet (`contracts/wallet`). It is its fifth variant, after
+/// `RegisterIdentity`, `VerifyIdentity`, `AddSessionKey` and `RemoveSessionKey`, and must be
+/// kept in sync with the wallet contract deployed on the node.
+#[derive(BorshDeserialize, Debug, PartialEq)]
+#[borsh(use_discriminant = true)]
+#[repr(u8)]
+enum WalletSessionAction {
+    UseSessionKey {
+        account: String,
+        /// Checked by the wallet contract.
+        #[allow(dead_code)]
+        nonce: u128,
+    } = 4,
+}
 
-#[derive(Debug)]
-struct AuthHeaders {
-    identity: String,
+impl WalletSessionAction {
+    /// Decodes the leading `UseSessionKey` of a wallet blob. Fields the wallet may append to
+    /// the variant are left to the wallet contract.
+    fn decode(data: &[u8]) -> Option<Self> {
+        Self::deserialize(&mut &data[..]).ok()
+    }
 }
 
-impl AuthHeaders {
-    fn from_headers(headers: &HeaderMap) -> Result<Self, AppError> {
-        let identity = headers
-            .get(IDENTITY_HEADER)
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| {
-                AppError(
-                    StatusCode::UNAUTHORIZED,
-                    anyhow::anyhow!("Missing identity"),
-                )
-            })?
-            .to_string();
+/// Checks that the wallet blobs are a session key action of the wallet contract for the account
+/// of `identity` and one secp256k1 signature for that identity, and returns the decoded
+/// signature blob. The nonce and the session key itself are left to the wallet contract.
+fn check_wallet_blobs(
+    blobs: &[Blob],
+    wallet_cn: &ContractName,
+    identity: &str,
+) -> Result<Secp256k1Blob, AppError> {
+    let bad_request = |msg: String| AppError(StatusCode::BAD_REQUEST, anyhow::anyhow!(msg));
+
+    if !identity.ends_with(&format!("@{}", wallet_cn.0)) {
+        return Err(bad_request(format!(
+            "Identity {identity} does not belong to the {wallet_cn} contract"
+        )));
+    }
 
-        Ok(AuthHeaders { identity })
+    let [first, second] = blobs else {
+        return Err(bad_request(format!(
+            "Expected 2 wallet blobs, got {}",
+            blobs.len()
+        )));
+    };
+    let (wallet_blob, secp_blob) = match (&first.contract_name, &second.contract_name) {
+        (cn, _) if cn == wallet_cn => (first, second),
+        (_, cn) if cn == wallet_cn => (second, first),
+        _ => {
+            return Err(bad_request(format!(
+                "Expected a blob for the {wallet_cn} contract, got {} and {}",
+                first.contract_name, second.contract_name
+            )))
+        }
+    };
+
+    let action = WalletSessionAction::decode(&wallet_blob.data.0)
+        .ok_or_else(|| bad_request(format!("Expected a session key action of {wallet_cn}")))?;
+    let WalletSessionAction::UseSessionKey { account, .. } = action;
+    if format!("{account}@{}", wallet_cn.0) != identity {
+        return Err(bad_request(format!(
+            "The {wallet_cn} action is for account {account}, not {identity}"
+        )));
+    }
+
+    let secp_blob = decode_secp256k1_blob(secp_blob).ok_or_else(|| {
+        bad_request(format!(
+            "Expected a well-formed {SECP256K1_CONTRACT} blob, got a {} blob",
+            secp_blob.contract_name
+        ))
+    })?;
+    if secp_blob.identity.0 != identity {
+        return Err(bad_request(format!(
+            "Wallet blobs are signed for {}, not {identity}",
+            secp_blob.identity.0
+        )));
     }
+
+    Ok(secp_blob)
 }
 
 // --------------------------------------------------------
@@ -151,10 +293,28 @@ impl AuthHeaders {
 #[derive(Serialize, Debug, Clone)]
 pub struct ApiGotchi {
     pub name: String,
+    /// Either `hyligotchi` or `aliengotchi`, to pick the assets to render.
+    pub species: String,
+    pub color: u8,
+    pub temperament: String,
+    pub metabolism: String,
+    pub stage: String,
     pub activity: String,
     pub food: u64,
     pub sweets: u64,
     pub vitamins: u64,
+    pub happiness: u64,
+    pub weight: u64,
+    pub poops: u64,
+    pub disease: Option<String>,
+}
+
+/// A gotchi of the caller, with the slot to act on it through `/api/gotchi/{slot}/...`.
+#[derive(Serialize, Debug)]
+pub struct ApiOwnedGotchi {
+    pub slot: u32,
+    #[serde(flatten)]
+    pub gotchi: ApiGotchi,
 }
 
 #[derive(Serialize, Debug)]
@@ -163,14 +323,55 @@ pub struct ApiResponse {
     pub tx_hash: String,
 }
 
+#[derive(Serialize, Debug)]
+pub struct ApiTxStatus {
+    pub tx_hash: String,
+    /// One of `pending`, `sequenced`, `settled` or `failed`.
+    pub status: &'static str,
+    /// The gotchi the transaction acted on, once executed.
+    pub gotchi: Option<ApiGotchi>,
+    pub error: Option<String>,
+}
+
+impl ApiTxStatus {
+    fn new(tx_hash: String, status: TxStatus) -> Self {
+        let name = status.name();
+        let (gotchi, error) = match status {
+            TxStatus::Pending => (None, None),
+            TxStatus::Sequenced(gotchi) | TxStatus::Settled(gotchi) => {
+                (gotchi.map(ApiGotchi::from), None)
+            }
+            TxStatus::Failed(error) => (None, Some(error)),
+        };
+        ApiTxStatus {
+            tx_hash,
+            status: name,
+            gotchi,
+            error,
+        }
+    }
+}
+
 impl From<HyliGotchi> for ApiGotchi {
     fn from(hyligotchi: HyliGotchi) -> Self {
         ApiGotchi {
             name: hyligotchi.name,
+            species: hyligotchi.genome.species().to_string(),
+            color: hyligotchi.genome.color(),
+            temperament: hyligotchi.genome.temperament().to_string(),
+            metabolism: hyligotchi.genome.metabolism().to_string(),
+            stage: hyligotchi.stage.to_string(),
             activity: hyligotchi.activity.to_string(),
             food: hyligotchi.food,
             sweets: hyligotchi.sweets,
             vitamins: hyligotchi.vitamins,
+            happiness: hyligotchi.happiness,
+            weight: hyligotchi.weight,
+            poops: hyligotchi.poops,
+            disease: match hyligotchi.health {
+                HyliGotchiHealth::Sick(disease, _) => Some(disease.to_string()),
+                _ => None,
+            },
         }
     }
 }
@@ -180,6 +381,17 @@ struct ConfigResponse {
     contract_name: String,
 }
 
+/// Query options shared by every player action route.
+#[derive(Deserialize, Default)]
+struct SendOptions {
+    /// Only run the action against the latest state and return the predicted gotchi.
+    #[serde(default)]
+    dry_run: bool,
+    /// Wait for the prover to run the transaction, instead of returning its hash right away.
+    #[serde(default)]
+    wait: bool,
+}
+
 #[derive(Deserialize)]
 struct InitWithName {
     name: String,
@@ -188,68 +400,311 @@ struct InitWithName {
 // --------------------------------------------------------
 //     Routes
 // --------------------------------------------------------
+//
+// Routes without a gotchi id act on the first gotchi (slot 0) of the caller.
 
 async fn init(
+    state: State<RouterCtx>,
+    headers: HeaderMap,
+    init_with_name: Query<InitWithName>,
+    options: Query<SendOptions>,
+    wallet_blobs: Json<[Blob; 2]>,
+) -> Result<impl IntoResponse, AppError> {
+    init_slot(
+        state,
+        headers,
+        Path(0),
+        init_with_name,
+        options,
+        wallet_blobs,
+    )
+    .await
+}
+
+async fn init_slot(
     State(ctx): State<RouterCtx>,
     headers: HeaderMap,
+    Path(slot): Path<u32>,
     Query(init_with_name): Query<InitWithName>,
+    Query(options): Query<SendOptions>,
     Json(wallet_blobs): Json<[Blob; 2]>,
 ) -> Result<impl IntoResponse, AppError> {
-    let auth = AuthHeaders::from_headers(&headers)?;
+    let auth = AuthHeaders::from_headers(&headers).map_err(AppError::from_indexer)?;
     send(
         ctx,
-        HyliGotchiAction::Init(Identity(auth.identity.clone()), init_with_name.name),
+        HyliGotchiAction::Init(
+            GotchiId::new(Identity(auth.identity.clone()), slot),
+            init_with_name.name,
+        ),
         auth,
         wallet_blobs.to_vec(),
+        options,
     )
     .await
 }
 
 async fn resurrect(
+    state: State<RouterCtx>,
+    headers: HeaderMap,
+    options: Query<SendOptions>,
+    wallet_blobs: Json<[Blob; 2]>,
+) -> Result<impl IntoResponse, AppError> {
+    resurrect_slot(state, headers, Path(0), options, wallet_blobs).await
+}
+
+async fn resurrect_slot(
     State(ctx): State<RouterCtx>,
     headers: HeaderMap,
+    Path(slot): Path<u32>,
+    Query(options): Query<SendOptions>,
     Json(wallet_blobs): Json<[Blob; 2]>,
 ) -> Result<impl IntoResponse, AppError> {
-    let auth = AuthHeaders::from_headers(&headers)?;
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map_err(|_| {
-            AppError(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                anyhow::anyhow!("Time error"),
-            )
-        })?
-        .as_millis();
+    let auth = AuthHeaders::from_headers(&headers).map_err(AppError::from_indexer)?;
+    // The cost is filled in from the latest state when the transaction is built
+    send(
+        ctx,
+        HyliGotchiAction::Resurrect(GotchiId::new(Identity(auth.identity.clone()), slot), 0),
+        auth,
+        wallet_blobs.to_vec(),
+        options,
+    )
+    .await
+}
+
+async fn play(
+    state: State<RouterCtx>,
+    headers: HeaderMap,
+    options: Query<SendOptions>,
+    wallet_blobs: Json<[Blob; 2]>,
+) -> Result<impl IntoResponse, AppError> {
+    play_slot(state, headers, Path(0), options, wallet_blobs).await
+}
+
+async fn play_slot(
+    State(ctx): State<RouterCtx>,
+    headers: HeaderMap,
+    Path(slot): Path<u32>,
+    Query(options): Query<SendOptions>,
+    Json(wallet_blobs): Json<[Blob; 2]>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth = AuthHeaders::from_headers(&headers).map_err(AppError::from_indexer)?;
+    let now = action_nonce()?;
+
+    send(
+        ctx,
+        HyliGotchiAction::Play(GotchiId::new(Identity(auth.identity.clone()), slot), now),
+        auth,
+        wallet_blobs.to_vec(),
+        options,
+    )
+    .await
+}
+
+async fn sleep(
+    state: State<RouterCtx>,
+    headers: HeaderMap,
+    options: Query<SendOptions>,
+    wallet_blobs: Json<[Blob; 2]>,
+) -> Result<impl IntoResponse, AppError> {
+    sleep_slot(state, headers, Path(0), options, wallet_blobs).await
+}
+
+async fn sleep_slot(
+    State(ctx): State<RouterCtx>,
+    headers: HeaderMap,
+    Path(slot): Path<u32>,
+    Query(options): Query<SendOptions>,
+    Json(wallet_blobs): Json<[Blob; 2]>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth = AuthHeaders::from_headers(&headers).map_err(AppError::from_indexer)?;
+    let now = action_nonce()?;
+
     send(
         ctx,
-        HyliGotchiAction::Resurrect(Identity(auth.identity.clone()), now),
+        HyliGotchiAction::Sleep(GotchiId::new(Identity(auth.identity.clone()), slot), now),
         auth,
         wallet_blobs.to_vec(),
+        options,
+    )
+    .await
+}
+
+async fn wake(
+    state: State<RouterCtx>,
+    headers: HeaderMap,
+    options: Query<SendOptions>,
+    wallet_blobs: Json<[Blob; 2]>,
+) -> Result<impl IntoResponse, AppError> {
+    wake_slot(state, headers, Path(0), options, wallet_blobs).await
+}
+
+async fn wake_slot(
+    State(ctx): State<RouterCtx>,
+    headers: HeaderMap,
+    Path(slot): Path<u32>,
+    Query(options): Query<SendOptions>,
+    Json(wallet_blobs): Json<[Blob; 2]>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth = AuthHeaders::from_headers(&headers).map_err(AppError::from_indexer)?;
+    let now = action_nonce()?;
+
+    send(
+        ctx,
+        HyliGotchiAction::Wake(GotchiId::new(Identity(auth.identity.clone()), slot), now),
+        auth,
+        wallet_blobs.to_vec(),
+        options,
     )
     .await
 }
 
 async fn clean_poop(
+    state: State<RouterCtx>,
+    headers: HeaderMap,
+    options: Query<SendOptions>,
+    wallet_blobs: Json<[Blob; 2]>,
+) -> Result<impl IntoResponse, AppError> {
+    clean_poop_slot(state, headers, Path(0), options, wallet_blobs).await
+}
+
+async fn clean_poop_slot(
     State(ctx): State<RouterCtx>,
     headers: HeaderMap,
+    Path(slot): Path<u32>,
+    Query(options): Query<SendOptions>,
     Json(wallet_blobs): Json<[Blob; 2]>,
 ) -> Result<impl IntoResponse, AppError> {
-    let auth = AuthHeaders::from_headers(&headers)?;
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map_err(|_| {
-            AppError(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                anyhow::anyhow!("Time error"),
-            )
-        })?
-        .as_millis();
+    let auth = AuthHeaders::from_headers(&headers).map_err(AppError::from_indexer)?;
+    let now = action_nonce()?;
+
+    send(
+        ctx,
+        HyliGotchiAction::CleanPoop(GotchiId::new(Identity(auth.identity.clone()), slot), now),
+        auth,
+        wallet_blobs.to_vec(),
+        options,
+    )
+    .await
+}
+
+#[derive(Deserialize)]
+struct TransferTo {
+    to: String,
+}
+
+/// Offers the caller's gotchi to another identity.
+async fn transfer(
+    State(ctx): State<RouterCtx>,
+    headers: HeaderMap,
+    Path(slot): Path<u32>,
+    Query(transfer_to): Query<TransferTo>,
+    Query(options): Query<SendOptions>,
+    Json(wallet_blobs): Json<[Blob; 2]>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth = AuthHeaders::from_headers(&headers).map_err(AppError::from_indexer)?;
+    send(
+        ctx,
+        HyliGotchiAction::Transfer(
+            GotchiId::new(Identity(auth.identity.clone()), slot),
+            Identity(transfer_to.to),
+        ),
+        auth,
+        wallet_blobs.to_vec(),
+        options,
+    )
+    .await
+}
+
+#[derive(Deserialize)]
+struct AdoptFrom {
+    owner: String,
+    slot: u32,
+}
+
+/// Takes a gotchi offered to the caller into the caller's slot `id`.
+async fn adopt(
+    State(ctx): State<RouterCtx>,
+    headers: HeaderMap,
+    Path(slot): Path<u32>,
+    Query(adopt_from): Query<AdoptFrom>,
+    Query(options): Query<SendOptions>,
+    Json(wallet_blobs): Json<[Blob; 2]>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth = AuthHeaders::from_headers(&headers).map_err(AppError::from_indexer)?;
+    send(
+        ctx,
+        HyliGotchiAction::Adopt(
+            GotchiId::new(Identity(adopt_from.owner), adopt_from.slot),
+            GotchiId::new(Identity(auth.identity.clone()), slot),
+        ),
+        auth,
+        wallet_blobs.to_vec(),
+        options,
+    )
+    .await
+}
+
+#[derive(Deserialize)]
+struct BreedingPartner {
+    partner_owner: String,
+    partner_slot: u32,
+}
+
+/// First step of breeding with another player: the owner of the partner gotchi lets the
+/// caller's gotchi breed with it.
+async fn allow_breeding(
+    State(ctx): State<RouterCtx>,
+    headers: HeaderMap,
+    Path(slot): Path<u32>,
+    Query(partner): Query<BreedingPartner>,
+    Query(options): Query<SendOptions>,
+    Json(wallet_blobs): Json<[Blob; 2]>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth = AuthHeaders::from_headers(&headers).map_err(AppError::from_indexer)?;
+    send(
+        ctx,
+        HyliGotchiAction::AllowBreeding(
+            GotchiId::new(Identity(auth.identity.clone()), slot),
+            GotchiId::new(Identity(partner.partner_owner), partner.partner_slot),
+        ),
+        auth,
+        wallet_blobs.to_vec(),
+        options,
+    )
+    .await
+}
 
+#[derive(Deserialize)]
+struct BreedWith {
+    partner_owner: String,
+    partner_slot: u32,
+    child_slot: u32,
+    name: String,
+}
+
+/// Second step of breeding: the caller's gotchi breeds with a partner that allowed it, or
+/// with another gotchi of the caller.
+async fn breed(
+    State(ctx): State<RouterCtx>,
+    headers: HeaderMap,
+    Path(slot): Path<u32>,
+    Query(breed_with): Query<BreedWith>,
+    Query(options): Query<SendOptions>,
+    Json(wallet_blobs): Json<[Blob; 2]>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth = AuthHeaders::from_headers(&headers).map_err(AppError::from_indexer)?;
+    let owner = Identity(auth.identity.clone());
     send(
         ctx,
-        HyliGotchiAction::CleanPoop(Identity(auth.identity.clone()), now),
+        HyliGotchiAction::Breed(
+            GotchiId::new(owner.clone(), slot),
+            GotchiId::new(Identity(breed_with.partner_owner), breed_with.partner_slot),
+            GotchiId::new(owner, breed_with.child_slot),
+            breed_with.name,
+        ),
         auth,
         wallet_blobs.to_vec(),
+        options,
     )
     .await
 }
@@ -264,22 +719,72 @@ pub enum FeedType {
     Food,
     Sweets,
     Vitamin,
+    Medicine,
 }
 
 impl FeedType {
-    fn token_name(&self) -> &'static str {
+    fn nutrient(&self) -> Nutrient {
         match self {
-            FeedType::Food => "oranj",
-            FeedType::Sweets => "oxygen",
-            FeedType::Vitamin => "vitamin",
+            FeedType::Food => Nutrient::Food,
+            FeedType::Sweets => Nutrient::Sweets,
+            FeedType::Vitamin => Nutrient::Vitamins,
+            FeedType::Medicine => Nutrient::Medicine,
         }
     }
 
-    fn to_action(&self, identity: Identity, amount: u64) -> HyliGotchiAction {
+    /// First token registered in the game rules for this feed type.
+    fn token(&self, rules: &GameRules) -> Result<NutrientToken, AppError> {
+        rules
+            .tokens
+            .iter()
+            .find(|token| token.nutrient == self.nutrient())
+            .cloned()
+            .ok_or_else(|| {
+                AppError(
+                    StatusCode::BAD_REQUEST,
+                    anyhow::anyhow!("No token registered for {}", self.nutrient()),
+                )
+            })
+    }
+
+    /// Lowers `amount` to the tokens the gotchi can still eat, as of the latest known state,
+    /// since the contract rejects overfeeding.
+    fn edible_amount(
+        &self,
+        world: &HyliGotchiWorld,
+        id: &GotchiId,
+        token: &NutrientToken,
+        amount: u64,
+    ) -> Result<u64, AppError> {
+        let Some(mut gotchi) = world.get(id).filter(|gotchi| !gotchi.name.is_empty()) else {
+            // Let the contract report the missing gotchi
+            return Ok(amount);
+        };
+        gotchi.catch_up(
+            &HyliGotchi::compute_key(id),
+            &world.rules,
+            world.last_block_height,
+        );
+
+        let Some(appetite) = gotchi.appetite(self.nutrient(), &world.rules) else {
+            return Ok(amount);
+        };
+        let edible = appetite / token.points_per_unit.max(1);
+        if edible == 0 {
+            return Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Gotchi {id} cannot take more {}", self.nutrient()),
+            ));
+        }
+        Ok(amount.min(edible))
+    }
+
+    fn to_action(&self, id: GotchiId, amount: u64) -> HyliGotchiAction {
         match self {
-            FeedType::Food => HyliGotchiAction::FeedFood(identity, amount),
-            FeedType::Sweets => HyliGotchiAction::FeedSweets(identity, amount),
-            FeedType::Vitamin => HyliGotchiAction::FeedVitamins(identity, amount),
+            FeedType::Food => HyliGotchiAction::FeedFood(id, amount),
+            FeedType::Sweets => HyliGotchiAction::FeedSweets(id, amount),
+            FeedType::Vitamin => HyliGotchiAction::FeedVitamins(id, amount),
+            FeedType::Medicine => HyliGotchiAction::Heal(id, amount),
         }
     }
 }
@@ -288,48 +793,175 @@ async fn feed_food(
     State(ctx): State<RouterCtx>,
     headers: HeaderMap,
     Query(feed_amount): Query<FeedAmount>,
+    Query(options): Query<SendOptions>,
     Json(wallet_blobs): Json<[Blob; 2]>,
 ) -> Result<impl IntoResponse, AppError> {
-    feed_generic(ctx, headers, feed_amount, wallet_blobs, FeedType::Food).await
+    feed_generic(
+        ctx,
+        headers,
+        0,
+        feed_amount,
+        options,
+        wallet_blobs,
+        FeedType::Food,
+    )
+    .await
+}
+
+async fn feed_food_slot(
+    State(ctx): State<RouterCtx>,
+    headers: HeaderMap,
+    Path(slot): Path<u32>,
+    Query(feed_amount): Query<FeedAmount>,
+    Query(options): Query<SendOptions>,
+    Json(wallet_blobs): Json<[Blob; 2]>,
+) -> Result<impl IntoResponse, AppError> {
+    feed_generic(
+        ctx,
+        headers,
+        slot,
+        feed_amount,
+        options,
+        wallet_blobs,
+        FeedType::Food,
+    )
+    .await
 }
 
 async fn feed_sweets(
     State(ctx): State<RouterCtx>,
     headers: HeaderMap,
     Query(feed_amount): Query<FeedAmount>,
+    Query(options): Query<SendOptions>,
+    Json(wallet_blobs): Json<[Blob; 2]>,
+) -> Result<impl IntoResponse, AppError> {
+    feed_generic(
+        ctx,
+        headers,
+        0,
+        feed_amount,
+        options,
+        wallet_blobs,
+        FeedType::Sweets,
+    )
+    .await
+}
+
+async fn feed_sweets_slot(
+    State(ctx): State<RouterCtx>,
+    headers: HeaderMap,
+    Path(slot): Path<u32>,
+    Query(feed_amount): Query<FeedAmount>,
+    Query(options): Query<SendOptions>,
     Json(wallet_blobs): Json<[Blob; 2]>,
 ) -> Result<impl IntoResponse, AppError> {
-    feed_generic(ctx, headers, feed_amount, wallet_blobs, FeedType::Sweets).await
+    feed_generic(
+        ctx,
+        headers,
+        slot,
+        feed_amount,
+        options,
+        wallet_blobs,
+        FeedType::Sweets,
+    )
+    .await
 }
 
 async fn feed_vitamins(
     State(ctx): State<RouterCtx>,
     headers: HeaderMap,
     Query(feed_amount): Query<FeedAmount>,
+    Query(options): Query<SendOptions>,
+    Json(wallet_blobs): Json<[Blob; 2]>,
+) -> Result<impl IntoResponse, AppError> {
+    feed_generic(
+        ctx,
+        headers,
+        0,
+        feed_amount,
+        options,
+        wallet_blobs,
+        FeedType::Vitamin,
+    )
+    .await
+}
+
+async fn feed_vitamins_slot(
+    State(ctx): State<RouterCtx>,
+    headers: HeaderMap,
+    Path(slot): Path<u32>,
+    Query(feed_amount): Query<FeedAmount>,
+    Query(options): Query<SendOptions>,
+    Json(wallet_blobs): Json<[Blob; 2]>,
+) -> Result<impl IntoResponse, AppError> {
+    feed_generic(
+        ctx,
+        headers,
+        slot,
+        feed_amount,
+        options,
+        wallet_blobs,
+        FeedType::Vitamin,
+    )
+    .await
+}
+
+async fn heal(
+    State(ctx): State<RouterCtx>,
+    headers: HeaderMap,
+    Query(feed_amount): Query<FeedAmount>,
+    Query(options): Query<SendOptions>,
     Json(wallet_blobs): Json<[Blob; 2]>,
 ) -> Result<impl IntoResponse, AppError> {
-    feed_generic(ctx, headers, feed_amount, wallet_blobs, FeedType::Vitamin).await
+    feed_generic(
+        ctx,
+        headers,
+        0,
+        feed_amount,
+        options,
+        wallet_blobs,
+        FeedType::Medicine,
+    )
+    .await
+}
+
+async fn heal_slot(
+    State(ctx): State<RouterCtx>,
+    headers: HeaderMap,
+    Path(slot): Path<u32>,
+    Query(feed_amount): Query<FeedAmount>,
+    Query(options): Query<SendOptions>,
+    Json(wallet_blobs): Json<[Blob; 2]>,
+) -> Result<impl IntoResponse, AppError> {
+    feed_generic(
+        ctx,
+        headers,
+        slot,
+        feed_amount,
+        options,
+        wallet_blobs,
+        FeedType::Medicine,
+    )
+    .await
 }
 
 async fn feed_generic(
     ctx: RouterCtx,
     headers: HeaderMap,
+    slot: u32,
     feed_amount: FeedAmount,
+    options: SendOptions,
     wallet_blobs: [Blob; 2],
     feed_type: FeedType,
 ) -> Result<impl IntoResponse, AppError> {
-    let auth = AuthHeaders::from_headers(&headers)?;
-    let identity = Identity(auth.identity.clone());
-    let action = feed_type.to_action(identity, feed_amount.amount);
+    let auth = AuthHeaders::from_headers(&headers).map_err(AppError::from_indexer)?;
+    let id = GotchiId::new(Identity(auth.identity.clone()), slot);
+    let action = feed_type.to_action(id, feed_amount.amount);
 
-    send(ctx, action, auth, wallet_blobs.to_vec()).await
+    send(ctx, action, auth, wallet_blobs.to_vec(), options).await
 }
 
-async fn trigger_tick(
-    State(ctx): State<RouterCtx>,
-    headers: HeaderMap,
-    Path(secret): Path<String>,
-) -> Result<impl IntoResponse, AppError> {
+fn check_admin_secret(secret: &str) -> Result<(), AppError> {
     // Get the expected secret from env or default to "test"
     let expected_secret = std::env::var("TICK_SECRET").unwrap_or_else(|_| "test".to_string());
 
@@ -339,27 +971,180 @@ async fn trigger_tick(
             anyhow::anyhow!("Invalid secret"),
         ));
     }
+    Ok(())
+}
 
-    let now = std::time::SystemTime::now()
+/// Nonce of a new action, the current time in milliseconds.
+fn action_nonce() -> Result<u128, AppError> {
+    Ok(std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .map_err(|_| anyhow::anyhow!("Time error"))?
-        .as_millis();
+        .as_millis())
+}
+
+/// Sends an action authorized by a signature of `data_to_sign` with the backend key.
+async fn send_backend_action(
+    ctx: RouterCtx,
+    action: HyliGotchiAction,
+    data_to_sign: &[u8],
+) -> Result<impl IntoResponse, AppError> {
+    let identity = Identity("hyligtochi_server@secp256k1".to_string());
+    let blob = create_secp256k1_blob(&ctx.crypto_context, &identity, data_to_sign)?;
 
-    let blob = create_secp256k1_blob(
-        &ctx.crypto_context,
-        &Identity("hyligtochi_server@secp256k1".to_string()),
-        now,
-    )?;
+    submit(ctx, action, identity, vec![blob], SendOptions::default()).await
+}
 
-    send(
+async fn trigger_tick(
+    State(ctx): State<RouterCtx>,
+    Path(secret): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    check_admin_secret(&secret)?;
+    let now = action_nonce()?;
+
+    send_backend_action(ctx, HyliGotchiAction::Tick(now), &tick_signed_data(now)).await
+}
+
+#[derive(Deserialize)]
+struct RotateKey {
+    /// Hex-encoded compressed secp256k1 public key of the new backend.
+    public_key: String,
+}
+
+/// Hands the backend role over to a new key. The server keeps signing with its current key,
+/// so it must be restarted with the matching `HYLIGOTCHI_PUBKEY` once the rotation settles.
+async fn rotate_backend_key(
+    State(ctx): State<RouterCtx>,
+    Path(secret): Path<String>,
+    Json(rotate_key): Json<RotateKey>,
+) -> Result<impl IntoResponse, AppError> {
+    check_admin_secret(&secret)?;
+
+    let new_key = secp256k1::PublicKey::from_str(&rotate_key.public_key)
+        .map_err(|e| AppError(StatusCode::BAD_REQUEST, anyhow::anyhow!(e)))?
+        .serialize();
+    let now = action_nonce()?;
+
+    send_backend_action(
+        ctx,
+        HyliGotchiAction::RotateBackendKey(now, new_key),
+        &rotate_backend_key_signed_data(now, &new_key),
+    )
+    .await
+}
+
+/// Wipes every gotchi from the world.
+async fn reset_world(
+    State(ctx): State<RouterCtx>,
+    Path(secret): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    check_admin_secret(&secret)?;
+    let now = action_nonce()?;
+
+    send_backend_action(
         ctx,
-        HyliGotchiAction::Tick(now),
-        AuthHeaders::from_headers(&headers)?,
-        vec![blob],
+        HyliGotchiAction::ResetWorld(now),
+        &reset_world_signed_data(now),
     )
     .await
 }
 
+/// Switches the world to new game rules, keeping its gotchis. Rules missing from the body keep
+/// their value in the latest state.
+/// Changing the layout of the gotchis requires a redeploy instead.
+async fn migrate(
+    State(ctx): State<RouterCtx>,
+    Path(secret): Path<String>,
+    Json(changes): Json<serde_json::Map<String, serde_json::Value>>,
+) -> Result<impl IntoResponse, AppError> {
+    check_admin_secret(&secret)?;
+    let rules = {
+        let world = ctx.world.read().await;
+        let world = world.as_ref().ok_or_else(|| {
+            AppError(
+                StatusCode::SERVICE_UNAVAILABLE,
+                anyhow::anyhow!("No state to migrate from yet"),
+            )
+        })?;
+        patch_rules(&world.rules, changes)?
+    };
+    let now = action_nonce()?;
+    let data_to_sign = migrate_signed_data(now, &rules);
+
+    send_backend_action(ctx, HyliGotchiAction::Migrate(now, rules), &data_to_sign).await
+}
+
+/// Game rules with the given fields replaced, rejecting fields that are not game rules.
+fn patch_rules(
+    rules: &GameRules,
+    changes: serde_json::Map<String, serde_json::Value>,
+) -> Result<GameRules, AppError> {
+    let mut fields = serde_json::to_value(rules)?;
+    for (name, value) in changes {
+        let Some(field) = fields.get_mut(&name) else {
+            return Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Unknown game rule {name}"),
+            ));
+        };
+        *field = value;
+    }
+    serde_json::from_value(fields).map_err(|e| AppError(StatusCode::BAD_REQUEST, e.into()))
+}
+
+/// Gotchis of the caller in the latest state, brought up to date with the chain head.
+async fn get_gotchis(
+    State(ctx): State<RouterCtx>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    let auth = AuthHeaders::from_headers(&headers).map_err(AppError::from_indexer)?;
+    let world = ctx.world.read().await;
+    let world = world.as_ref().ok_or_else(|| {
+        AppError(
+            StatusCode::SERVICE_UNAVAILABLE,
+            anyhow::anyhow!("No state to read the gotchis from yet"),
+        )
+    })?;
+
+    auth.check_session_key(world)
+        .map_err(AppError::from_indexer)?;
+
+    let gotchis = world
+        .owned_gotchis(&Identity(auth.identity))
+        .into_iter()
+        .map(|owned| ApiOwnedGotchi {
+            slot: owned.slot,
+            gotchi: owned.gotchi.into(),
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Json(gotchis))
+}
+
+async fn get_tx(
+    State(ctx): State<RouterCtx>,
+    headers: HeaderMap,
+    Path(hash): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let auth = AuthHeaders::from_headers(&headers).map_err(AppError::from_indexer)?;
+    // The gotchi is only shown to an identity whose session key is known on chain.
+    let reader = ctx
+        .world
+        .read()
+        .await
+        .as_ref()
+        .is_some_and(|world| auth.check_session_key(world).is_ok())
+        .then_some(Identity(auth.identity));
+
+    let status = ctx
+        .txs
+        .read()
+        .await
+        .status(&TxHash(hash.clone()), reader.as_ref())
+        .ok_or_else(|| AppError(StatusCode::NOT_FOUND, anyhow::anyhow!("Unknown tx {hash}")))?;
+
+    Ok(Json(ApiTxStatus::new(hash, status)))
+}
+
 async fn get_config(State(ctx): State<RouterCtx>) -> impl IntoResponse {
     Json(ConfigResponse {
         contract_name: ctx.hyligotchi_cn.0,
@@ -370,76 +1155,255 @@ async fn send(
     ctx: RouterCtx,
     action: HyliGotchiAction,
     auth: AuthHeaders,
+    blobs: Vec<Blob>,
+    options: SendOptions,
+) -> Result<Response, AppError> {
+    let secp_blob = check_wallet_blobs(&blobs, &ctx.wallet_cn, &auth.identity)?;
+    auth.check_session_signature(&secp_blob)
+        .map_err(AppError::from_indexer)?;
+
+    submit(ctx, action, Identity(auth.identity), blobs, options).await
+}
+
+/// Adds the blobs of `action` to the authorizing `blobs` and sends the transaction.
+/// Returns its hash right away, or waits for the prover to run it if asked to.
+async fn submit(
+    ctx: RouterCtx,
+    action: HyliGotchiAction,
+    identity: Identity,
     mut blobs: Vec<Blob>,
-) -> Result<impl IntoResponse, AppError> {
-    let identity = Identity(auth.identity);
+    options: SendOptions,
+) -> Result<Response, AppError> {
+    let gotchi_id = action.gotchi_id().cloned();
 
     match action {
-        HyliGotchiAction::FeedFood(ref identity, amount) => {
-            handle_feed_action(amount, &ctx, identity, &mut blobs, FeedType::Food).await?;
+        HyliGotchiAction::FeedFood(ref id, amount) => {
+            handle_feed_action(amount, &ctx, id, &mut blobs, FeedType::Food).await?;
+        }
+        HyliGotchiAction::FeedSweets(ref id, amount) => {
+            handle_feed_action(amount, &ctx, id, &mut blobs, FeedType::Sweets).await?;
         }
-        HyliGotchiAction::FeedSweets(ref identity, amount) => {
-            handle_feed_action(amount, &ctx, identity, &mut blobs, FeedType::Sweets).await?;
+        HyliGotchiAction::FeedVitamins(ref id, amount) => {
+            handle_feed_action(amount, &ctx, id, &mut blobs, FeedType::Vitamin).await?;
         }
-        HyliGotchiAction::FeedVitamins(ref identity, amount) => {
-            handle_feed_action(amount, &ctx, identity, &mut blobs, FeedType::Vitamin).await?;
+        HyliGotchiAction::Heal(ref id, amount) => {
+            handle_feed_action(amount, &ctx, id, &mut blobs, FeedType::Medicine).await?;
+        }
+        HyliGotchiAction::Resurrect(ref id, _) => {
+            handle_resurrect_action(&ctx, id, &mut blobs).await;
         }
         _ => {
             blobs.push(action.as_blob(ctx.hyligotchi_cn.clone()));
         }
     }
 
-    let tx_hash = ctx
-        .client
-        .send_tx_blob(BlobTransaction::new(identity.clone(), blobs))
-        .await?;
+    let tx = BlobTransaction::new(identity, blobs);
+
+    // Player actions are first run against the latest state, so that the contract's error
+    // comes back right away instead of after the tx fails on chain.
+    if let Some(id) = &gotchi_id {
+        let predicted = simulate(&ctx, id, &tx).await?;
+        if options.dry_run {
+            let predicted = predicted.ok_or_else(|| {
+                AppError(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    anyhow::anyhow!("No state to run the action against yet"),
+                )
+            })?;
+            return Ok(Json(ApiGotchi::from(predicted)).into_response());
+        }
+    }
 
-    let mut bus = {
-        let app = ctx.app.lock().await;
-        AppModuleBusClient::new_from_bus(app.bus.new_handle()).await
+    // Tracked before sending, so that the prover cannot run it before anyone waits for it.
+    let tx_hash = tx.hashed();
+    let (newly_tracked, waiter) = {
+        let mut txs = ctx.txs.write().await;
+        let newly_tracked = txs.track(tx_hash.clone(), gotchi_id);
+        (newly_tracked, options.wait.then(|| txs.wait(&tx_hash)))
     };
 
-    tokio::time::timeout(Duration::from_secs(30), async {
-        loop {
-            let a = bus.recv().await?;
-            match a {
-                AutoProverEvent::SuccessTx(sequenced_tx_hash, state) => {
-                    if sequenced_tx_hash == tx_hash {
-                        // let balance = state.balances.get(&identity).copied().unwrap_or(0);
-                        let gotchi: ApiGotchi = state.get(&identity).unwrap_or_default().into();
-                        return Ok(Json(ApiResponse {
-                            gotchi,
-                            tx_hash: tx_hash.to_string(),
-                        }));
-                    }
-                }
-                AutoProverEvent::FailedTx(sequenced_tx_hash, error) => {
-                    if sequenced_tx_hash == tx_hash {
-                        return Err(AppError(StatusCode::BAD_REQUEST, anyhow::anyhow!(error)));
-                    }
-                }
-            }
+    if let Err(e) = ctx.client.send_tx_blob(tx).await {
+        // A copy sent earlier may still land, otherwise the tx can be retried from scratch.
+        if newly_tracked {
+            ctx.txs.write().await.forget(&tx_hash);
         }
-    })
-    .await?
+        return Err(e.into());
+    }
+
+    let Some(waiter) = waiter else {
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(ApiTxStatus::new(tx_hash.to_string(), TxStatus::Pending)),
+        )
+            .into_response());
+    };
+
+    match tokio::time::timeout(Duration::from_secs(30), waiter).await {
+        Ok(Ok(TxStatus::Sequenced(gotchi) | TxStatus::Settled(gotchi))) => Ok(Json(ApiResponse {
+            gotchi: gotchi.unwrap_or_default().into(),
+            tx_hash: tx_hash.to_string(),
+        })
+        .into_response()),
+        Ok(Ok(TxStatus::Failed(error))) => {
+            Err(AppError(StatusCode::BAD_REQUEST, anyhow::anyhow!(error)))
+        }
+        Ok(Ok(TxStatus::Pending) | Err(_)) => Err(AppError(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            anyhow::anyhow!("Stopped tracking tx {tx_hash}"),
+        )),
+        Err(_) => {
+            ctx.txs.write().await.drop_closed_waiters(&tx_hash);
+            Err(AppError(
+                StatusCode::GATEWAY_TIMEOUT,
+                anyhow::anyhow!("Tx {tx_hash} is still pending, see /api/tx/{tx_hash}"),
+            ))
+        }
+    }
+}
+
+/// Runs the action of `tx` against the latest state known to the prover, and returns the
+/// gotchi `id` as the action would leave it, or `None` before any state is known.
+/// The tx lands in a later block, so anything drawn from the block, like a genome, or decayed
+/// since may still differ.
+async fn simulate(
+    ctx: &RouterCtx,
+    id: &GotchiId,
+    tx: &BlobTransaction,
+) -> Result<Option<HyliGotchi>, AppError> {
+    let world = ctx.world.read().await;
+    let Some(world) = world.as_ref() else {
+        return Ok(None);
+    };
+
+    // Feeding may have lowered the amount, so the action is read back from the tx.
+    let index = tx
+        .blobs
+        .iter()
+        .position(|blob| blob.contract_name == ctx.hyligotchi_cn)
+        .ok_or_else(|| anyhow::anyhow!("Missing {} blob", ctx.hyligotchi_cn))?;
+    let action = HyliGotchiAction::from_blob_data(&tx.blobs[index].data)?;
+    let calldata = Calldata {
+        tx_hash: tx.hashed(),
+        identity: tx.identity.clone(),
+        blobs: tx.blobs.clone().into(),
+        tx_blob_count: tx.blobs.len(),
+        index: BlobIndex(index),
+        tx_ctx: Some(TxContext {
+            block_hash: world.last_block_hash.clone(),
+            block_height: BlockHeight(world.last_block_height),
+            ..Default::default()
+        }),
+        private_input: vec![],
+    };
+
+    let ids = action.gotchi_ids();
+    let gotchis = world
+        .simulate(action, &ctx.hyligotchi_cn, &calldata)
+        .map_err(|e| AppError(StatusCode::BAD_REQUEST, anyhow::anyhow!(e)))?;
+
+    Ok(ids
+        .into_iter()
+        .zip(gotchis)
+        .find_map(|(gotchi_id, gotchi)| (gotchi_id == *id).then_some(gotchi)))
+}
+
+/// Pays for the resurrection of the gotchi, at the cost computed from the latest state.
+async fn handle_resurrect_action(ctx: &RouterCtx, id: &GotchiId, blobs: &mut Vec<Blob>) {
+    let (token, cost) = match ctx.world.read().await.as_ref() {
+        Some(world) => {
+            // Let the contract report a missing gotchi
+            let cost = world
+                .get(id)
+                .filter(|gotchi| !gotchi.name.is_empty())
+                .map(|mut gotchi| {
+                    gotchi.catch_up(
+                        &HyliGotchi::compute_key(id),
+                        &world.rules,
+                        world.last_block_height,
+                    );
+                    gotchi.resurrect_cost(&world.rules)
+                })
+                .unwrap_or_default();
+            (world.rules.resurrect_token.clone(), cost)
+        }
+        None => (ctx.game_rules.resurrect_token.clone(), 0),
+    };
+
+    blobs.push(HyliGotchiAction::Resurrect(id.clone(), cost).as_blob(ctx.hyligotchi_cn.clone()));
+    if cost != 0 {
+        let transfer_action = SmtTokenAction::Transfer {
+            sender: id.owner.clone(),
+            recipient: ctx.hyligotchi_cn.0.clone().into(),
+            amount: cost as u128,
+        };
+        blobs.push(transfer_action.as_blob(token, None, None));
+    }
 }
 
 async fn handle_feed_action(
     amount: u64,
     ctx: &RouterCtx,
-    identity: &Identity,
+    id: &GotchiId,
     blobs: &mut Vec<Blob>,
     feed_type: FeedType,
 ) -> Result<(), AppError> {
+    // Rules migrated on chain take over the ones the server was started with.
+    let (token, amount) = match ctx.world.read().await.as_ref() {
+        Some(world) => {
+            let token = feed_type.token(&world.rules)?;
+            let amount = feed_type.edible_amount(world, id, &token, amount)?;
+            (token, amount)
+        }
+        None => (feed_type.token(&ctx.game_rules)?, amount),
+    };
+
     let transfer_action = SmtTokenAction::Transfer {
-        sender: identity.clone(),
+        sender: id.owner.clone(),
         recipient: ctx.hyligotchi_cn.0.clone().into(),
         amount: amount as u128,
     };
 
-    let feed_action = feed_type.to_action(identity.clone(), amount);
+    let feed_action = feed_type.to_action(id.clone(), amount);
     blobs.push(feed_action.as_blob(ctx.hyligotchi_cn.clone()));
-    blobs.push(transfer_action.as_blob(feed_type.token_name().into(), None, None));
+    blobs.push(transfer_action.as_blob(token.contract_name, None, None));
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `WalletAction::UseSessionKey { account: "alice", nonce: 7 }` as the wallet contract
+    /// encodes it.
+    const USE_SESSION_KEY: &str = "0405000000616c69636507000000000000000000000000000000";
+
+    #[test]
+    fn decodes_the_wallet_use_session_key_action() {
+        let data = hex::decode(USE_SESSION_KEY).unwrap();
+        assert_eq!(
+            WalletSessionAction::decode(&data),
+            Some(WalletSessionAction::UseSessionKey {
+                account: "alice".to_string(),
+                nonce: 7,
+            })
+        );
+
+        // Trailing bytes are left to the wallet contract.
+        let mut extended = data.clone();
+        extended.extend_from_slice(&[1, 2, 3]);
+        assert_eq!(
+            WalletSessionAction::decode(&extended),
+            WalletSessionAction::decode(&data)
+        );
+    }
+
+    #[test]
+    fn rejects_other_wallet_actions() {
+        let mut data = hex::decode(USE_SESSION_KEY).unwrap();
+        // `RemoveSessionKey` comes right before `UseSessionKey`.
+        data[0] = 3;
+        assert_eq!(WalletSessionAction::decode(&data), None);
+        assert_eq!(WalletSessionAction::decode(&[]), None);
+    }
+}
@@ -78,6 +78,7 @@ async fn main() -> Result<()> {
 
     let constructor = HyliGotchiWorldConstructor {
         backend_pubkey: public_key.serialize(),
+        rules: config.game_rules.clone(),
     };
 
     let world = HyliGotchiWorld::new(&constructor);
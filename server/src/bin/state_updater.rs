@@ -1,13 +1,11 @@
 use std::{sync::Arc, time::Duration};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::Parser;
 use client_sdk::rest_client::{IndexerApiHttpClient, NodeApiClient, NodeApiHttpClient};
-use client_sdk::transaction_builder::TxExecutorHandler;
 use hyle_modules::utils::logger::setup_tracing;
 use hyligotchi::{
-    client::{HyliGotchiWorld, HyliGotchiWorldConstructor, Metadata},
-    HyliGotchiAction, HyliGotchiWorldZkView,
+    client::Metadata, reset_world_signed_data, HyliGotchiAction, HyliGotchiWorldZkView,
 };
 use sdk::{
     info, BlobIndex, BlobTransaction, BlockHeight, Calldata, ConsensusProposalHash, ContractName,
@@ -17,6 +15,8 @@ use secp256k1::{PublicKey, Secp256k1, SecretKey};
 use server::{conf::Conf, utils::load_pk};
 use tokio::time::timeout;
 
+/// Proves a signed `ResetWorld` from the state published by the indexer, for when the
+/// autoprover cannot, e.g. after its own state got lost.
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 pub struct Args {
@@ -36,11 +36,11 @@ async fn main() -> Result<()> {
     let config = Conf::new(args.config_file).context("reading config file")?;
     setup_tracing(
         &config.log_format,
-        format!("{}(autoprover)", config.id.clone(),),
+        format!("{}(state_updater)", config.id.clone(),),
     )
     .context("setting up tracing")?;
     let config = Arc::new(config);
-    info!("Starting autoprover with config: {:?}", &config);
+    info!("Starting state updater with config: {:?}", &config);
 
     let node_client = NodeApiHttpClient::new(config.node_url.clone())?;
     let indexer_client = IndexerApiHttpClient::new(config.indexer_url.clone())?;
@@ -65,17 +65,6 @@ async fn main() -> Result<()> {
         public_key,
     };
 
-    let constructor = HyliGotchiWorldConstructor {
-        backend_pubkey: public_key.serialize(),
-    };
-
-    let world = HyliGotchiWorld::new(&constructor);
-    let new_initial_state = world.get_state_commitment();
-
-    tracing::info!(
-        "New initial state will be: {:?}",
-        hex::encode(new_initial_state.0.clone())
-    );
     tracing::info!("Getting initial state");
     let initial_state = indexer_client
         .get_indexer_contract(&ContractName::new(args.contract_name.clone()))
@@ -92,32 +81,38 @@ async fn main() -> Result<()> {
     .await?;
 
     tracing::info!("Metadata: {:?}", metadata);
-    let commitment_metadata =
-        build_commitment_metadata(StateCommitment(initial_state), new_initial_state, metadata)?;
+    if metadata.backend_pubkey != public_key.serialize() {
+        bail!("HYLIGOTCHI_PUBKEY is not the backend key of the contract");
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_err(|_| anyhow::anyhow!("Time error"))?
+        .as_millis();
+    if now <= metadata.last_backend_nonce {
+        bail!("Backend nonce {now} is not above the last one used on chain");
+    }
+
+    let commitment_metadata = build_commitment_metadata(StateCommitment(initial_state), &metadata)?;
 
     tracing::info!(
         "Commitment metadata: {:?}",
         hex::encode(commitment_metadata.clone())
     );
 
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map_err(|_| anyhow::anyhow!("Time error"))?
-        .as_millis();
+    let identity = Identity::new("hyligtochi_server@secp256k1");
 
     let secp_blob = server::ticker_module::create_secp256k1_blob(
         &crypto_context,
-        &Identity("hyligtochi_server@secp256k1".to_string()),
-        now,
+        &identity,
+        &reset_world_signed_data(now),
     )?;
 
-    // Create the Tick action blob
-    let action_blob = HyliGotchiAction::Tick(now).as_blob(args.contract_name.clone().into());
+    // Create the ResetWorld action blob
+    let action_blob = HyliGotchiAction::ResetWorld(now).as_blob(args.contract_name.clone().into());
 
     let blobs = vec![secp_blob, action_blob];
 
-    let identity = Identity::new("hyligtochi_server@secp256k1");
-
     let blob_tx = BlobTransaction::new(identity.clone(), blobs.clone());
     tracing::info!("Sending Blob TX: {:?}", blob_tx);
     let tx_hash = node_client.send_tx_blob(blob_tx.clone()).await?;
@@ -173,18 +168,16 @@ async fn main() -> Result<()> {
 
 fn build_commitment_metadata(
     initial_state: sdk::StateCommitment,
-    commitment: sdk::StateCommitment,
-    metadata: Metadata,
+    metadata: &Metadata,
 ) -> anyhow::Result<Vec<u8>> {
-    let tick_data = Some((
-        commitment,
-        metadata.last_block_hash.clone(),
-        metadata.last_block_height,
-    ));
     let zk_view = HyliGotchiWorldZkView {
         commitment: initial_state,
+        root: metadata.root,
+        gotchi_count: metadata.gotchi_count,
+        tick_data: vec![],
         backend_pubkey: metadata.backend_pubkey,
-        tick_data,
+        rules: metadata.rules.clone(),
+        last_backend_nonce: metadata.last_backend_nonce,
         partial_data: vec![],
     };
     borsh::to_vec(&zk_view).context("Failed to serialize WalletZkView for commitment metadata")
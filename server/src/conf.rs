@@ -1,5 +1,6 @@
 use config::{Config, Environment, File};
 use hyle_modules::modules::websocket::WebSocketConfig;
+use hyligotchi::GameRules;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -28,6 +29,14 @@ pub struct Conf {
 
     pub tick_interval_secs: u64,
 
+    /// Wallet contract that player identities must belong to, as in `name@wallet`.
+    pub wallet_contract_name: String,
+
+    /// Game balance of the world registered at startup. Changing it changes the initial state.
+    /// Values left out of the config fall back to `GameRules::default()`.
+    #[serde(default)]
+    pub game_rules: GameRules,
+
     /// Websocket configuration
     pub websocket: WebSocketConfig,
 }
@@ -1,4 +1,5 @@
 pub mod app;
 pub mod conf;
 pub mod ticker_module;
+pub mod tx_tracker;
 pub mod utils;
@@ -34,6 +34,7 @@ use crate::ticker_module::TickerModule;
 mod app;
 mod init;
 mod ticker_module;
+mod tx_tracker;
 mod utils;
 
 #[derive(Parser, Debug)]
@@ -95,6 +96,7 @@ async fn main() -> Result<()> {
 
     let constructor = HyliGotchiWorldConstructor {
         backend_pubkey: public_key.serialize(),
+        rules: config.game_rules.clone(),
     };
 
     let world = HyliGotchiWorld::new(&constructor);
@@ -155,6 +157,9 @@ async fn main() -> Result<()> {
         node_client,
         hyligotchi_cn: args.contract_name.into(),
         crypto_context: Arc::new(crypto_context),
+        game_rules: config.game_rules.clone(),
+        wallet_cn: config.wallet_contract_name.clone().into(),
+        data_directory: config.data_directory.clone(),
     });
 
     handler.build_module::<AppModule>(app_ctx.clone()).await?;
@@ -4,7 +4,7 @@ use hyle_modules::{
     module_bus_client,
     modules::{signal::shutdown_aware, Module},
 };
-use hyligotchi::HyliGotchiAction;
+use hyligotchi::{tick_signed_data, HyliGotchiAction};
 use sdk::{verifiers::Secp256k1Blob, Blob, BlobTransaction, ContractName, Identity};
 use secp256k1::Message;
 use sha2::{Digest, Sha256};
@@ -70,7 +70,7 @@ impl Module for TickerModule {
                 let blob = create_secp256k1_blob(
                     &crypto_context,
                     &Identity("hyligtochi_server@secp256k1".to_string()),
-                    now,
+                    &tick_signed_data(now),
                 )?;
 
                 // Create the Tick action blob
@@ -93,14 +93,11 @@ impl Module for TickerModule {
 pub fn create_secp256k1_blob(
     crypto: &CryptoContext,
     identity: &Identity,
-    nonce: u128,
+    data_to_sign: &[u8],
 ) -> anyhow::Result<Blob> {
     // Let's create a secp2561k1 blob signing the data
-    let mut data_to_sign = nonce.to_le_bytes().to_vec();
-    data_to_sign.extend_from_slice("HyliGotchiWorldTick".as_bytes());
-
     let mut hasher = Sha256::new();
-    hasher.update(data_to_sign.clone());
+    hasher.update(data_to_sign);
     let message_hash: [u8; 32] = hasher.finalize().into();
     let signature = crypto
         .secp
@@ -108,7 +105,7 @@ pub fn create_secp256k1_blob(
 
     Ok(Secp256k1Blob::new(
         identity.clone(),
-        &data_to_sign,
+        data_to_sign,
         &crypto.public_key.to_string(),
         &signature.to_string(),
     )?
@@ -0,0 +1,158 @@
+use std::collections::{HashMap, VecDeque};
+
+use hyligotchi::{client::HyliGotchiWorld, GotchiId, HyliGotchi};
+use sdk::{Identity, TxHash};
+use tokio::sync::oneshot;
+
+/// Transactions tracked at most, the oldest ones being forgotten first.
+pub const MAX_TRACKED_TXS: usize = 10_000;
+
+/// Progress of a transaction sent by the server.
+#[derive(Debug, Clone)]
+pub enum TxStatus {
+    /// Sent to the node, not seen in a block yet.
+    Pending,
+    /// Executed by the prover once sequenced, with the gotchi it acted on, waiting for its proof.
+    Sequenced(Option<HyliGotchi>),
+    /// Proven and settled on chain.
+    Settled(Option<HyliGotchi>),
+    /// Rejected by the contract, or failed or timed out on chain.
+    Failed(String),
+}
+
+impl TxStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TxStatus::Pending => "pending",
+            TxStatus::Sequenced(_) => "sequenced",
+            TxStatus::Settled(_) => "settled",
+            TxStatus::Failed(_) => "failed",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TrackedTx {
+    gotchi_id: Option<GotchiId>,
+    status: TxStatus,
+    /// Requests waiting for the prover to run the transaction.
+    waiters: Vec<oneshot::Sender<TxStatus>>,
+}
+
+impl TrackedTx {
+    fn set_status(&mut self, status: TxStatus) {
+        for waiter in self.waiters.drain(..) {
+            let _ = waiter.send(status.clone());
+        }
+        self.status = status;
+    }
+}
+
+/// Status of the latest transactions sent by the server, fed by prover and node events.
+/// Forgetting a transaction drops its waiters, so memory stays bounded.
+#[derive(Debug, Default)]
+pub struct TxTracker {
+    txs: HashMap<TxHash, TrackedTx>,
+    order: VecDeque<TxHash>,
+}
+
+impl TxTracker {
+    /// Starts tracking a transaction, before it is sent so that no event can be missed.
+    /// A transaction sent again keeps its status, as it is the very same one.
+    /// Returns whether the transaction was not tracked yet.
+    pub fn track(&mut self, tx_hash: TxHash, gotchi_id: Option<GotchiId>) -> bool {
+        if self.txs.contains_key(&tx_hash) {
+            return false;
+        }
+        if self.order.len() >= MAX_TRACKED_TXS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.txs.remove(&oldest);
+            }
+        }
+        self.order.push_back(tx_hash.clone());
+        self.txs.insert(
+            tx_hash,
+            TrackedTx {
+                gotchi_id,
+                status: TxStatus::Pending,
+                waiters: vec![],
+            },
+        );
+        true
+    }
+
+    /// Stops tracking a transaction that could not be sent, so that sending it again starts
+    /// afresh. Its waiters are dropped.
+    pub fn forget(&mut self, tx_hash: &TxHash) {
+        if self.txs.remove(tx_hash).is_some() {
+            self.order.retain(|hash| hash != tx_hash);
+        }
+    }
+
+    /// Resolves once the prover ran the tracked transaction, or right away if it already did.
+    /// The receiver errors if the transaction is not tracked or gets forgotten.
+    pub fn wait(&mut self, tx_hash: &TxHash) -> oneshot::Receiver<TxStatus> {
+        let (sender, receiver) = oneshot::channel();
+        if let Some(tx) = self.txs.get_mut(tx_hash) {
+            match tx.status {
+                TxStatus::Pending => tx.waiters.push(sender),
+                _ => {
+                    let _ = sender.send(tx.status.clone());
+                }
+            }
+        }
+        receiver
+    }
+
+    /// Drops the waiters that gave up on the transaction.
+    pub fn drop_closed_waiters(&mut self, tx_hash: &TxHash) {
+        if let Some(tx) = self.txs.get_mut(tx_hash) {
+            tx.waiters.retain(|waiter| !waiter.is_closed());
+        }
+    }
+
+    /// Status of the transaction as shown to `reader`, the gotchi being left out for anyone but
+    /// its owner.
+    pub fn status(&self, tx_hash: &TxHash, reader: Option<&Identity>) -> Option<TxStatus> {
+        let tx = self.txs.get(tx_hash)?;
+        let owned = tx
+            .gotchi_id
+            .as_ref()
+            .is_some_and(|id| Some(&id.owner) == reader);
+        Some(match &tx.status {
+            TxStatus::Sequenced(_) if !owned => TxStatus::Sequenced(None),
+            TxStatus::Settled(_) if !owned => TxStatus::Settled(None),
+            status => status.clone(),
+        })
+    }
+
+    /// The prover ran the transaction successfully, leaving the world in `state`.
+    pub fn sequenced(&mut self, tx_hash: &TxHash, state: &HyliGotchiWorld) {
+        if let Some(tx) = self.txs.get_mut(tx_hash) {
+            if let TxStatus::Pending = tx.status {
+                let gotchi = tx
+                    .gotchi_id
+                    .as_ref()
+                    .map(|id| state.get(id).unwrap_or_default());
+                tx.set_status(TxStatus::Sequenced(gotchi));
+            }
+        }
+    }
+
+    pub fn settled(&mut self, tx_hash: &TxHash) {
+        if let Some(tx) = self.txs.get_mut(tx_hash) {
+            if let TxStatus::Sequenced(gotchi) = &tx.status {
+                tx.status = TxStatus::Settled(gotchi.clone());
+            }
+        }
+    }
+
+    /// Keeps the first error reported, which is the most precise one.
+    pub fn failed(&mut self, tx_hash: &TxHash, error: String) {
+        if let Some(tx) = self.txs.get_mut(tx_hash) {
+            if !matches!(tx.status, TxStatus::Failed(_)) {
+                tx.set_status(TxStatus::Failed(error));
+            }
+        }
+    }
+}
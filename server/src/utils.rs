@@ -36,6 +36,13 @@ where
     }
 }
 
+impl AppError {
+    /// Keeps the status of errors from the contract's indexer helpers, like its auth checks.
+    pub fn from_indexer(err: client_sdk::contract_indexer::AppError) -> Self {
+        Self(err.0, err.1)
+    }
+}
+
 #[allow(dead_code)]
 pub fn load_pk(data_directory: &Path) -> SP1ProvingKey {
     let pk_path = data_directory.join("proving_key.bin");